@@ -0,0 +1,158 @@
+use crate::challenges::Part;
+use std::collections::BTreeMap;
+use std::fs;
+
+pub const DEFAULT_PATH: &str = "data/answers.toml";
+
+/// Expected answers keyed by day and part, read from a file like
+///
+/// ```toml
+/// [day_05]
+/// part_one = "CMZ"
+/// part_two = "MCD"
+/// ```
+#[derive(Debug, PartialEq)]
+pub struct Answers {
+    expected: BTreeMap<(u8, Part), String>,
+}
+
+fn parse_day(key: &str) -> Option<u8> {
+    key.strip_prefix("day_")?.parse().ok()
+}
+
+fn parse_part(key: &str) -> Option<Part> {
+    match key {
+        "part_one" => Some(Part::One),
+        "part_two" => Some(Part::Two),
+        _ => None,
+    }
+}
+
+fn parse_answer(key: &str, part_key: &str, answer: &toml::Value) -> Result<(Part, String), String> {
+    let part = parse_part(part_key)
+        .ok_or_else(|| format!("expected part_one or part_two in {key}, found {part_key}"))?;
+    let answer = match answer {
+        toml::Value::String(answer) => answer.clone(),
+        toml::Value::Integer(answer) => answer.to_string(),
+        _ => return Err(format!("{key}.{part_key} must be a string or an integer")),
+    };
+    Ok((part, answer))
+}
+
+/// Parses the answers of a single example, given as top level `part_one` and `part_two` keys.
+pub fn parse_parts(contents: &str) -> Result<BTreeMap<Part, String>, String> {
+    let table: toml::Table = contents.parse().map_err(|err| format!("{err}"))?;
+    table
+        .iter()
+        .map(|(part_key, answer)| parse_answer("the example answers", part_key, answer))
+        .collect()
+}
+
+impl Answers {
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        let table: toml::Table = contents.parse().map_err(|err| format!("{err}"))?;
+        let mut expected = BTreeMap::new();
+        for (day_key, parts) in table.iter() {
+            let day = parse_day(day_key)
+                .ok_or_else(|| format!("expected a table like [day_05], found [{day_key}]"))?;
+            let parts = parts
+                .as_table()
+                .ok_or_else(|| format!("[{day_key}] must be a table"))?;
+            for (part_key, answer) in parts.iter() {
+                let (part, answer) = parse_answer(&format!("[{day_key}]"), part_key, answer)?;
+                expected.insert((day, part), answer);
+            }
+        }
+        Ok(Answers { expected })
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let contents =
+            fs::read_to_string(path).map_err(|err| format!("could not read {path}: {err}"))?;
+        Answers::parse(&contents).map_err(|err| format!("invalid {path}: {err}"))
+    }
+
+    pub fn expected(&self, day: u8, part: Part) -> Option<&str> {
+        self.expected
+            .get(&(day, part))
+            .map(|answer| answer.as_str())
+    }
+}
+
+/// Result of comparing a computed answer with the recorded one.
+#[derive(Debug, PartialEq)]
+pub enum Verdict {
+    Pass,
+    Fail,
+    Missing,
+}
+
+impl Verdict {
+    pub fn of(expected: Option<&str>, actual: Option<&str>) -> Self {
+        match (expected, actual) {
+            (Some(expected), Some(actual)) if expected == actual => Verdict::Pass,
+            (Some(_), _) => Verdict::Fail,
+            (None, _) => Verdict::Missing,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Verdict::Pass => "pass",
+            Verdict::Fail => "FAIL",
+            Verdict::Missing => "missing",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_parts, Answers, Verdict};
+    use crate::challenges::Part;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_parse_answers() {
+        let answers = Answers::parse(
+            "[day_05]
+part_one = \"CMZ\"
+part_two = \"MCD\"
+
+[day_07]
+part_one = 95437",
+        )
+        .unwrap();
+
+        assert_eq!(answers.expected(5, Part::One), Some("CMZ"));
+        assert_eq!(answers.expected(5, Part::Two), Some("MCD"));
+        assert_eq!(answers.expected(7, Part::One), Some("95437"));
+        assert_eq!(answers.expected(7, Part::Two), None);
+    }
+
+    #[test]
+    fn test_parse_invalid_answers() {
+        assert!(Answers::parse("[five]\npart_one = 1").is_err());
+        assert!(Answers::parse("[day_05]\npart_three = 1").is_err());
+        assert!(Answers::parse("[day_05]\npart_one = 1.5").is_err());
+    }
+
+    #[test]
+    fn test_parse_parts() {
+        assert_eq!(
+            parse_parts("part_one = 24000\npart_two = \"45000\""),
+            Ok(BTreeMap::from([
+                (Part::One, "24000".to_string()),
+                (Part::Two, "45000".to_string())
+            ]))
+        );
+        assert!(parse_parts("part_3 = 1").is_err());
+    }
+
+    #[test]
+    fn test_verdict() {
+        assert_eq!(Verdict::of(Some("CMZ"), Some("CMZ")), Verdict::Pass);
+        assert_eq!(Verdict::of(Some("CMZ"), Some("MCD")), Verdict::Fail);
+        assert_eq!(Verdict::of(Some("CMZ"), None), Verdict::Fail);
+        assert_eq!(Verdict::of(None, Some("CMZ")), Verdict::Missing);
+    }
+}
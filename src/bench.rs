@@ -0,0 +1,131 @@
+use crate::challenges::{Part, Puzzle};
+use crate::error::Result;
+use std::time::Duration;
+
+/// Summary of repeated timings of the same step.
+#[derive(Debug, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    /// Summarises the timings, `None` when there are none.
+    pub fn from_durations(durations: &[Duration]) -> Option<Self> {
+        let mut sorted = durations.to_vec();
+        sorted.sort();
+        let min = *sorted.first()?;
+
+        let middle = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) {
+            (sorted[middle - 1] + sorted[middle]) / 2
+        } else {
+            sorted[middle]
+        };
+
+        let seconds: Vec<f64> = sorted.iter().map(|d| d.as_secs_f64()).collect();
+        let mean = seconds.iter().sum::<f64>() / seconds.len() as f64;
+        let variance = seconds
+            .iter()
+            .map(|second| (second - mean).powi(2))
+            .sum::<f64>()
+            / seconds.len() as f64;
+
+        Some(Stats {
+            min,
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        })
+    }
+}
+
+/// Parse and solve timings of one part, measured separately.
+#[derive(Debug)]
+pub struct Measurement {
+    pub answer: String,
+    pub parse: Stats,
+    pub solve: Stats,
+}
+
+/// Runs a part `warmup` times without measuring and then `iterations` times with measuring.
+/// Fails with the error of the first run if the part has no answer for the input.
+pub fn measure(
+    puzzle: &dyn Puzzle,
+    input: &str,
+    part: Part,
+    warmup: usize,
+    iterations: usize,
+) -> Result<Measurement> {
+    let answer = puzzle.solve(input, part)?;
+    for _ in 0..warmup {
+        puzzle.run(input, part);
+    }
+
+    let mut parse_times = Vec::with_capacity(iterations);
+    let mut solve_times = Vec::with_capacity(iterations);
+    for _ in 0..iterations.max(1) {
+        let run = puzzle.run(input, part);
+        parse_times.push(run.parse_time);
+        solve_times.push(run.solve_time);
+    }
+
+    Ok(Measurement {
+        answer,
+        parse: Stats::from_durations(&parse_times).unwrap(),
+        solve: Stats::from_durations(&solve_times).unwrap(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{measure, Stats};
+    use crate::challenges::{find, Part};
+    use crate::error::Error;
+    use std::time::Duration;
+
+    #[test]
+    fn test_stats() {
+        let durations: Vec<Duration> = [4, 2, 8, 6]
+            .iter()
+            .map(|millis| Duration::from_millis(*millis))
+            .collect();
+
+        let stats = Stats::from_durations(&durations).unwrap();
+        assert_eq!(stats.min, Duration::from_millis(2));
+        assert_eq!(stats.median, Duration::from_millis(5));
+        assert_eq!(stats.mean, Duration::from_millis(5));
+        assert_eq!(stats.stddev.as_micros(), 2236);
+    }
+
+    #[test]
+    fn test_stats_without_durations() {
+        assert_eq!(Stats::from_durations(&[]), None);
+    }
+
+    #[test]
+    fn test_measure() {
+        let puzzle = find(6).unwrap();
+        let measurement = measure(
+            puzzle.as_ref(),
+            "mjqjpqmgbljsphdztnvjfqwrcgsmlb",
+            Part::Two,
+            1,
+            3,
+        )
+        .unwrap();
+        assert_eq!(measurement.answer, "19");
+        assert!(measurement.solve.min <= measurement.solve.median);
+    }
+
+    #[test]
+    fn test_measure_without_answer() {
+        let puzzle = find(6).unwrap();
+        assert!(matches!(
+            measure(puzzle.as_ref(), "aaaa", Part::One, 0, 1),
+            Err(Error::Invalid { .. })
+        ));
+    }
+}
@@ -1,3 +1,10 @@
+use crate::error::Result;
+use crate::input;
+use serde::{Serialize, Serializer};
+use std::fmt::Display;
+use std::io::Read;
+use std::time::{Duration, Instant};
+
 pub mod day_01;
 pub mod day_02;
 pub mod day_03;
@@ -5,3 +12,151 @@ pub mod day_04;
 pub mod day_05;
 pub mod day_06;
 pub mod day_07;
+
+/// One of the two halves every daily puzzle consists of.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn number(self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+
+    pub fn from_number(number: u8) -> Option<Self> {
+        match number {
+            1 => Some(Part::One),
+            2 => Some(Part::Two),
+            _ => None,
+        }
+    }
+}
+
+/// Common shape of a daily solution: the input is parsed once and then handed to both parts.
+pub trait Solution {
+    type Input;
+    type Answer: Display;
+
+    fn day(&self) -> u8;
+
+    fn parse(&self, input: &str) -> Result<Self::Input>;
+
+    fn part_one(&self, input: &Self::Input) -> Result<Self::Answer>;
+
+    fn part_two(&self, input: &Self::Input) -> Result<Self::Answer>;
+}
+
+/// Serialized as the number of the part, like on the command line.
+impl Serialize for Part {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_u8(self.number())
+    }
+}
+
+/// Answer of a single part together with the time spent on parsing and on solving.
+#[derive(Debug)]
+pub struct Run {
+    pub answer: Result<String>,
+    pub parse_time: Duration,
+    pub solve_time: Duration,
+}
+
+/// Object safe view on a [`Solution`], so that all days can be kept in one registry.
+pub trait Puzzle {
+    fn day(&self) -> u8;
+
+    fn run(&self, input: &str, part: Part) -> Run;
+
+    fn solve(&self, input: &str, part: Part) -> Result<String> {
+        self.run(input, part).answer
+    }
+
+    /// Solves a part with the input read from any reader, e.g. standard input.
+    fn solve_from(&self, reader: &mut dyn Read, part: Part) -> Result<String> {
+        self.solve(&input::read(reader)?, part)
+    }
+}
+
+impl<S: Solution> Puzzle for S {
+    fn day(&self) -> u8 {
+        Solution::day(self)
+    }
+
+    fn run(&self, input: &str, part: Part) -> Run {
+        let start = Instant::now();
+        let parsed = self.parse(input);
+        let parse_time = start.elapsed();
+
+        let start = Instant::now();
+        let answer = parsed.and_then(|parsed| match part {
+            Part::One => self.part_one(&parsed),
+            Part::Two => self.part_two(&parsed),
+        });
+        let answer = answer.map(|answer| answer.to_string());
+        let solve_time = start.elapsed();
+
+        Run {
+            answer,
+            parse_time,
+            solve_time,
+        }
+    }
+}
+
+/// All implemented days, ordered by day.
+pub fn registry() -> Vec<Box<dyn Puzzle>> {
+    vec![
+        Box::new(day_01::Day01),
+        Box::new(day_02::Day02),
+        Box::new(day_03::Day03),
+        Box::new(day_04::Day04),
+        Box::new(day_05::Day05),
+        Box::new(day_06::Day06),
+        Box::new(day_07::Day07::default()),
+    ]
+}
+
+pub fn find(day: u8) -> Option<Box<dyn Puzzle>> {
+    registry().into_iter().find(|puzzle| puzzle.day() == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find, registry, Part};
+
+    #[test]
+    fn test_registry_is_ordered_by_day() {
+        let days: Vec<u8> = registry().iter().map(|puzzle| puzzle.day()).collect();
+        assert_eq!(days, vec![1, 2, 3, 4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn test_find() {
+        assert_eq!(
+            find(1).unwrap().solve("1\n2\n\n4", Part::Two),
+            Ok("7".to_string())
+        );
+        assert!(find(25).is_none());
+        assert_eq!(
+            find(6).unwrap().solve_from(
+                &mut "mjqjpqmgbljsphdztnvjfqwrcgsmlb\n".as_bytes(),
+                Part::One
+            ),
+            Ok("7".to_string())
+        );
+    }
+
+    #[test]
+    fn test_part_numbers() {
+        assert_eq!(Part::from_number(2), Some(Part::Two));
+        assert_eq!(Part::from_number(3), None);
+        assert_eq!(Part::One.number(), 1);
+    }
+}
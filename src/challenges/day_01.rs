@@ -1,35 +1,109 @@
-pub fn group_sums(input_lines: Vec<&str>) -> Vec<i32> {
-    input_lines.into_iter().fold(vec![0], |mut prev, curr| {
-        if !curr.is_empty() {
-            let counter = prev.pop().unwrap() + curr.parse::<i32>().unwrap();
-            prev.push(counter);
-        } else {
-            prev.push(0);
-        }
-        prev
-    })
+use crate::challenges::Solution;
+use crate::error::{Error, Result};
+
+pub fn group_sums(input_lines: Vec<&str>) -> Result<Vec<i32>> {
+    input_lines
+        .into_iter()
+        .enumerate()
+        .try_fold(vec![0i32], |mut prev, (index, curr)| {
+            if !curr.is_empty() {
+                let calories = curr
+                    .parse::<i32>()
+                    .map_err(|_| Error::parse(1, index + 1, 1, curr, "expected a number"))?;
+                let counter = prev
+                    .pop()
+                    .unwrap_or(0)
+                    .checked_add(calories)
+                    .ok_or_else(|| {
+                        Error::parse(1, index + 1, 1, curr, "the calories of this elf overflow")
+                    })?;
+                prev.push(counter);
+            } else {
+                prev.push(0);
+            }
+            Ok(prev)
+        })
 }
 
-pub fn top_tree_elfs(mut counts: Vec<i32>) -> i32 {
+pub fn top_tree_elfs(mut counts: Vec<i32>) -> Result<i32> {
     counts.sort();
-    let max = counts.get(counts.len() - 1).unwrap();
-    let second = counts.get(counts.len() - 2).unwrap();
-    let third = counts.get(counts.len() - 3).unwrap();
-    max + second + third
+    counts
+        .iter()
+        .rev()
+        .take(3)
+        .try_fold(0i32, |sum, count| sum.checked_add(*count))
+        .ok_or_else(|| Error::invalid(1, "the calories of the top three elfs overflow"))
+}
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    type Input = Vec<i32>;
+    type Answer = i32;
+
+    fn day(&self) -> u8 {
+        1
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        group_sums(input.lines().collect())
+    }
+
+    fn part_one(&self, input: &Self::Input) -> Result<Self::Answer> {
+        input
+            .iter()
+            .max()
+            .copied()
+            .ok_or_else(|| Error::invalid(1, "no elf carries any calories"))
+    }
+
+    fn part_two(&self, input: &Self::Input) -> Result<Self::Answer> {
+        top_tree_elfs(input.clone())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::group_sums;
     use super::top_tree_elfs;
+    use crate::error::Error;
 
     #[test]
     fn test_grouping() {
-        assert_eq!(group_sums(vec!["1", "2", "", "2", "4"]), vec![3, 6]);
+        assert_eq!(group_sums(vec!["1", "2", "", "2", "4"]), Ok(vec![3, 6]));
+    }
+
+    #[test]
+    fn test_grouping_invalid_calories() {
+        assert_eq!(
+            group_sums(vec!["1", "", "2x"]),
+            Err(Error::parse(1, 3, 1, "2x", "expected a number"))
+        );
     }
 
     #[test]
     fn test_top_tree() {
-        assert_eq!(top_tree_elfs(vec![5, 1, 7, 8, 2]), 20);
+        assert_eq!(top_tree_elfs(vec![5, 1, 7, 8, 2]), Ok(20));
+    }
+
+    #[test]
+    fn test_calories_overflow() {
+        assert_eq!(
+            group_sums(vec!["2000000000", "2000000000"]),
+            Err(Error::parse(
+                1,
+                2,
+                1,
+                "2000000000",
+                "the calories of this elf overflow"
+            ))
+        );
+        assert_eq!(
+            top_tree_elfs(vec![2000000000, 2000000000]),
+            Err(Error::invalid(
+                1,
+                "the calories of the top three elfs overflow"
+            ))
+        );
     }
 }
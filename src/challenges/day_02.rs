@@ -1,6 +1,9 @@
-use std::collections::HashMap;
+use crate::challenges::Solution;
+use crate::error::{Error, Result};
+use std::str::FromStr;
 
-enum Move {
+#[derive(Debug, PartialEq)]
+pub enum Move {
     Rock,
     Paper,
     Scissor,
@@ -27,17 +30,7 @@ impl Move {
         }
     }
 
-    fn from(code: &str) -> Self {
-        if code == "X" || code == "A" {
-            return Move::Rock;
-        }
-        if code == "Y" || code == "B" {
-            return Move::Paper;
-        }
-        return Move::Scissor;
-    }
-
-    fn to_win(&self) -> Self {
+    pub fn to_win(&self) -> Self {
         match self {
             Move::Rock => Move::Paper,
             Move::Paper => Move::Scissor,
@@ -45,7 +38,7 @@ impl Move {
         }
     }
 
-    fn to_loose(&self) -> Self {
+    pub fn to_loose(&self) -> Self {
         match self {
             Move::Rock => Move::Scissor,
             Move::Paper => Move::Rock,
@@ -53,7 +46,7 @@ impl Move {
         }
     }
 
-    fn to_draw(&self) -> Self {
+    pub fn to_draw(&self) -> Self {
         match self {
             Move::Rock => Move::Rock,
             Move::Paper => Move::Paper,
@@ -61,7 +54,7 @@ impl Move {
         }
     }
 
-    fn move_value(&self) -> i32 {
+    pub fn move_value(&self) -> i32 {
         match self {
             Move::Rock => 1,
             Move::Paper => 2,
@@ -70,53 +63,135 @@ impl Move {
     }
 }
 
-pub fn group_sums(input_lines: Vec<&str>) -> Vec<i32> {
+/// Reads the codes of the strategy guide, `A` or `X` for rock, `B` or `Y` for paper and `C` or
+/// `Z` for scissors.
+impl FromStr for Move {
+    type Err = Error;
+
+    fn from_str(code: &str) -> Result<Self> {
+        match code {
+            "A" | "X" => Ok(Move::Rock),
+            "B" | "Y" => Ok(Move::Paper),
+            "C" | "Z" => Ok(Move::Scissor),
+            _ => Err(Error::parse(
+                2,
+                1,
+                1,
+                code,
+                "expected a move like A, B, C, X, Y or Z",
+            )),
+        }
+    }
+}
+
+fn split_moves(line_number: usize, line: &str) -> Result<(&str, &str)> {
+    let mut splitted = line.split_whitespace();
+    let oponent = splitted
+        .next()
+        .ok_or_else(|| Error::parse(2, line_number, 1, line, "expected a move like A, B or C"))?;
+    if !["A", "B", "C"].contains(&oponent) {
+        return Err(Error::parse(
+            2,
+            line_number,
+            line.find(oponent).unwrap_or(0) + 1,
+            line,
+            "expected a move like A, B or C",
+        ));
+    }
+    let own = splitted.next().ok_or_else(|| {
+        Error::parse(
+            2,
+            line_number,
+            line.len() + 1,
+            line,
+            "expected a second column with X, Y or Z",
+        )
+    })?;
+    if !["X", "Y", "Z"].contains(&own) {
+        return Err(Error::parse(
+            2,
+            line_number,
+            line.rfind(own).unwrap_or(0) + 1,
+            line,
+            "expected X, Y or Z",
+        ));
+    }
+    Ok((oponent, own))
+}
+
+/// Reads every line of the strategy guide as the move of the oponent and the code in the second
+/// column, which part one plays as a move and part two reads as the result to aim for.
+pub fn parse_moves(input_lines: Vec<&str>) -> Result<Vec<(Move, Move)>> {
     input_lines
         .into_iter()
-        .map(|line| {
-            let mut splitted = line.split_whitespace();
-            let oponent = splitted.next().unwrap();
-            let own_move = splitted.next().unwrap();
-            evaluate_move(oponent, own_move)
+        .enumerate()
+        .map(|(index, line)| {
+            let (oponent, own_move) = split_moves(index + 1, line)?;
+            Ok((oponent.parse()?, own_move.parse()?))
         })
         .collect()
 }
 
-fn evaluate_move(oponent: &str, own: &str) -> i32 {
-    let own_move_value_map: HashMap<&str, i32> = HashMap::from([("X", 1), ("Y", 2), ("Z", 3)]);
-    let move_points = own_move_value_map.get(own).unwrap();
-    let win_points = Move::from(own).outcome(&Move::from(oponent));
-    move_points + win_points
+pub fn group_sums(moves: &[(Move, Move)]) -> Vec<i32> {
+    moves
+        .iter()
+        .map(|(oponent, own_move)| evaluate_move(oponent, own_move))
+        .collect()
 }
 
-pub fn group_sums_part_two(input_lines: Vec<&str>) -> Vec<i32> {
-    input_lines
-        .into_iter()
-        .map(|line| {
-            let mut splitted = line.split_whitespace();
-            let oponent = splitted.next().unwrap();
-            let own_move = splitted.next().unwrap();
-            evaluate_what_to_choose(oponent, own_move)
-        })
+fn evaluate_move(oponent: &Move, own: &Move) -> i32 {
+    own.move_value() + own.outcome(oponent)
+}
+
+pub fn group_sums_part_two(moves: &[(Move, Move)]) -> Vec<i32> {
+    moves
+        .iter()
+        .map(|(oponent, strategy)| evaluate_what_to_choose(oponent, strategy))
         .collect()
 }
 
-fn evaluate_what_to_choose(oponent: &str, strategy: &str) -> i32 {
-    let own_move_fn_map: HashMap<&str, fn(&Move) -> Move> = HashMap::from([
-        ("X", Move::to_loose as fn(&Move) -> Move),
-        ("Y", Move::to_draw as fn(&Move) -> Move),
-        ("Z", Move::to_win as fn(&Move) -> Move),
-    ]);
-    let own_move = own_move_fn_map.get(strategy).unwrap()(&Move::from(oponent));
-    let win_points = own_move.outcome(&Move::from(oponent));
+/// `strategy` is the code of the second column: X (rock) to loose, Y (paper) to draw and Z
+/// (scissors) to win.
+fn evaluate_what_to_choose(oponent: &Move, strategy: &Move) -> i32 {
+    let own_move = match strategy {
+        Move::Rock => oponent.to_loose(),
+        Move::Paper => oponent.to_draw(),
+        Move::Scissor => oponent.to_win(),
+    };
+    let win_points = own_move.outcome(oponent);
     own_move.move_value() + win_points
 }
 
+pub struct Day02;
+
+impl Solution for Day02 {
+    type Input = Vec<(Move, Move)>;
+    type Answer = i32;
+
+    fn day(&self) -> u8 {
+        2
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse_moves(input.lines().collect())
+    }
+
+    fn part_one(&self, input: &Self::Input) -> Result<Self::Answer> {
+        Ok(group_sums(input).iter().sum())
+    }
+
+    fn part_two(&self, input: &Self::Input) -> Result<Self::Answer> {
+        Ok(group_sums_part_two(input).iter().sum())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::group_sums;
     use super::group_sums_part_two;
+    use super::parse_moves;
     use super::Move;
+    use crate::error::Error;
 
     #[test]
     fn test_moves_outcome() {
@@ -131,16 +206,60 @@ mod tests {
         assert_eq!(Move::Scissor.outcome(&Move::Paper), 6);
     }
 
+    #[test]
+    fn test_parse_move() {
+        assert_eq!("A".parse(), Ok(Move::Rock));
+        assert_eq!("Y".parse(), Ok(Move::Paper));
+        assert_eq!("C".parse(), Ok(Move::Scissor));
+        assert_eq!(
+            "Q".parse::<Move>(),
+            Err(Error::parse(
+                2,
+                1,
+                1,
+                "Q",
+                "expected a move like A, B, C, X, Y or Z"
+            ))
+        );
+    }
+
     #[test]
     fn test_outcome() {
-        assert_eq!(group_sums(vec!["A Y", "B X", "C Z"]), vec![8, 1, 6])
+        let moves = parse_moves(vec!["A Y", "B X", "C Z"]).unwrap();
+        assert_eq!(group_sums(&moves), vec![8, 1, 6])
     }
 
     #[test]
-    fn test_evaluate_what_to_choose() {
+    fn test_invalid_moves() {
         assert_eq!(
-            group_sums_part_two(vec!["A Y", "B X", "C Z"]),
-            vec![4, 1, 7]
-        )
+            parse_moves(vec!["A Y", "B Q"]),
+            Err(Error::parse(2, 2, 3, "B Q", "expected X, Y or Z"))
+        );
+        assert_eq!(
+            parse_moves(vec!["D Y"]),
+            Err(Error::parse(
+                2,
+                1,
+                1,
+                "D Y",
+                "expected a move like A, B or C"
+            ))
+        );
+        assert_eq!(
+            parse_moves(vec!["A"]),
+            Err(Error::parse(
+                2,
+                1,
+                2,
+                "A",
+                "expected a second column with X, Y or Z"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_evaluate_what_to_choose() {
+        let moves = parse_moves(vec!["A Y", "B X", "C Z"]).unwrap();
+        assert_eq!(group_sums_part_two(&moves), vec![4, 1, 7])
     }
 }
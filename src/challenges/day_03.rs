@@ -1,28 +1,35 @@
+use crate::challenges::Solution;
+use crate::error::{Error, Result};
+
 fn split_half(line: &str) -> (&str, &str) {
     line.split_at(line.len() / 2)
 }
 
 fn find_doublette(compartements: (&str, &str)) -> Option<char> {
     let (left, right) = compartements;
-    for c in left.chars() {
-        if right.contains(c) {
-            return Some(c);
-        }
-    }
-    return None;
+    left.chars().find(|c| right.contains(*c))
 }
 
 fn find_grouped_doublette(group: Vec<&str>) -> Option<char> {
-    let bag_1 = group.get(0).unwrap().chars();
+    let bag_1 = group.first().unwrap().chars();
     let bag_2 = group.get(1).unwrap().to_owned();
     let bag_3 = group.get(2).unwrap().to_owned();
     let intersection: Vec<char> = bag_1
         .filter(|char_bag_one| bag_2.contains(*char_bag_one) && bag_3.contains(*char_bag_one))
         .collect();
-    intersection.get(0).map(|c| c.to_owned())
+    intersection.first().map(|c| c.to_owned())
 }
 
-fn group_into_three_packages(input_lines: Vec<&str>) -> Vec<Vec<&str>> {
+fn group_into_three_packages(input_lines: Vec<&str>) -> Result<Vec<Vec<&str>>> {
+    if !input_lines.len().is_multiple_of(3) {
+        return Err(Error::invalid(
+            3,
+            &format!(
+                "expected groups of three rucksacks, found {} lines",
+                input_lines.len()
+            ),
+        ));
+    }
     let mut output: Vec<Vec<&str>> = Vec::new();
     let mut input_copy: Vec<&str> = input_lines.clone();
     input_copy.reverse();
@@ -33,7 +40,7 @@ fn group_into_three_packages(input_lines: Vec<&str>) -> Vec<Vec<&str>> {
             input_copy.pop().unwrap(),
         ])
     }
-    output
+    Ok(output)
 }
 
 fn evaluate_priority(letter: char) -> Option<i32> {
@@ -45,28 +52,113 @@ fn evaluate_priority(letter: char) -> Option<i32> {
         .map(|position| position as i32 + 1)
 }
 
-pub fn group_sums(input_lines: Vec<&str>) -> Vec<i32> {
+fn priority_on_line(doublette: char, line_number: usize, line: &str) -> Result<i32> {
+    evaluate_priority(doublette).ok_or_else(|| {
+        Error::parse(
+            3,
+            line_number,
+            line.chars().position(|c| c == doublette).unwrap_or(0) + 1,
+            line,
+            "expected an item between a and Z",
+        )
+    })
+}
+
+/// The items of a rucksack, every one of them a letter between a and Z.
+#[derive(Debug, PartialEq)]
+pub struct Rucksack {
+    items: String,
+}
+
+impl Rucksack {
+    pub fn items(&self) -> &str {
+        &self.items
+    }
+
+    fn compartments(&self) -> (&str, &str) {
+        split_half(&self.items)
+    }
+}
+
+pub fn parse_rucksacks(input_lines: Vec<&str>) -> Result<Vec<Rucksack>> {
     input_lines
         .into_iter()
-        .map(|line| {
-            let splitted = split_half(line);
-            let doublette = find_doublette(splitted).unwrap();
-            evaluate_priority(doublette).unwrap()
+        .enumerate()
+        .map(|(index, line)| {
+            if let Some(column) = line.chars().position(|c| !c.is_ascii_alphabetic()) {
+                return Err(Error::parse(
+                    3,
+                    index + 1,
+                    column + 1,
+                    line,
+                    "expected an item between a and Z",
+                ));
+            }
+            Ok(Rucksack {
+                items: line.to_string(),
+            })
+        })
+        .collect()
+}
+
+pub fn group_sums(rucksacks: &[Rucksack]) -> Result<Vec<i32>> {
+    rucksacks
+        .iter()
+        .enumerate()
+        .map(|(index, rucksack)| {
+            let line = rucksack.items();
+            let doublette = find_doublette(rucksack.compartments()).ok_or_else(|| {
+                Error::parse(3, index + 1, 1, line, "no item is in both compartments")
+            })?;
+            priority_on_line(doublette, index + 1, line)
         })
         .collect()
 }
 
-pub fn group_sums_part_two(input_lines: Vec<&str>) -> Vec<i32> {
-    let grouped_parts = group_into_three_packages(input_lines);
+pub fn group_sums_part_two(rucksacks: &[Rucksack]) -> Result<Vec<i32>> {
+    let grouped_parts = group_into_three_packages(rucksacks.iter().map(Rucksack::items).collect())?;
     grouped_parts
         .into_iter()
-        .map(|line| {
-            let doublette = find_grouped_doublette(line);
-            evaluate_priority(doublette.unwrap()).unwrap()
+        .enumerate()
+        .map(|(index, group)| {
+            let first_line = group[0];
+            let doublette = find_grouped_doublette(group).ok_or_else(|| {
+                Error::parse(
+                    3,
+                    index * 3 + 1,
+                    1,
+                    first_line,
+                    "no item is carried by all three elfs of the group",
+                )
+            })?;
+            priority_on_line(doublette, index * 3 + 1, first_line)
         })
         .collect()
 }
 
+pub struct Day03;
+
+impl Solution for Day03 {
+    type Input = Vec<Rucksack>;
+    type Answer = i32;
+
+    fn day(&self) -> u8 {
+        3
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse_rucksacks(input.lines().collect())
+    }
+
+    fn part_one(&self, input: &Self::Input) -> Result<Self::Answer> {
+        Ok(group_sums(input)?.iter().sum())
+    }
+
+    fn part_two(&self, input: &Self::Input) -> Result<Self::Answer> {
+        Ok(group_sums_part_two(input)?.iter().sum())
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -75,7 +167,22 @@ mod tests {
     use super::find_grouped_doublette;
     use super::group_into_three_packages;
     use super::group_sums;
+    use super::parse_rucksacks;
     use super::split_half;
+    use super::Rucksack;
+    use crate::error::Error;
+
+    fn example() -> Vec<Rucksack> {
+        parse_rucksacks(vec![
+            "vJrwpWtwJgWrhcsFMMfFFhFp",
+            "jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL",
+            "PmmdzqPrVvPwwTWBwg",
+            "wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn",
+            "ttgJtRGJQctTZtZT",
+            "CrZsJsPPZsGzwwsLwLmpwMDw",
+        ])
+        .unwrap()
+    }
 
     #[test]
     fn test_split() {
@@ -102,17 +209,7 @@ mod tests {
 
     #[test]
     fn test_group_part_one() {
-        assert_eq!(
-            group_sums(vec![
-                "vJrwpWtwJgWrhcsFMMfFFhFp",
-                "jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL",
-                "PmmdzqPrVvPwwTWBwg",
-                "wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn",
-                "ttgJtRGJQctTZtZT",
-                "CrZsJsPPZsGzwwsLwLmpwMDw"
-            ]),
-            vec![16, 38, 42, 22, 20, 19]
-        )
+        assert_eq!(group_sums(&example()), Ok(vec![16, 38, 42, 22, 20, 19]))
     }
 
     #[test]
@@ -146,7 +243,7 @@ mod tests {
                 "ttgJtRGJQctTZtZT",
                 "CrZsJsPPZsGzwwsLwLmpwMDw"
             ]),
-            vec![
+            Ok(vec![
                 vec![
                     "vJrwpWtwJgWrhcsFMMfFFhFp",
                     "jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL",
@@ -157,24 +254,45 @@ mod tests {
                     "ttgJtRGJQctTZtZT",
                     "CrZsJsPPZsGzwwsLwLmpwMDw"
                 ]
-            ]
+            ])
         )
     }
 
     #[test]
-    fn test_sum() {
+    fn test_incomplete_group() {
+        assert!(group_into_three_packages(vec!["vJrwpWtwJgWrhcsFMMfFFhFp"]).is_err());
+    }
+
+    #[test]
+    fn test_rucksack_without_doublette() {
         assert_eq!(
-            group_sums(vec![
-                "vJrwpWtwJgWrhcsFMMfFFhFp",
-                "jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL",
-                "PmmdzqPrVvPwwTWBwg",
-                "wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn",
-                "ttgJtRGJQctTZtZT",
-                "CrZsJsPPZsGzwwsLwLmpwMDw"
-            ])
-            .iter()
-            .sum::<i32>(),
-            157
-        )
+            group_sums(&parse_rucksacks(vec!["vJrwpWtwJgWrhcsFMMfFFhFp", "abcdef"]).unwrap()),
+            Err(Error::parse(
+                3,
+                2,
+                1,
+                "abcdef",
+                "no item is in both compartments"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_invalid_item() {
+        assert_eq!(
+            parse_rucksacks(vec!["vJrwpWtwJgWrhcsFMMfFFhFp", "ab1d"]),
+            Err(Error::parse(
+                3,
+                2,
+                3,
+                "ab1d",
+                "expected an item between a and Z"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_sum() {
+        assert_eq!(group_sums(&example()).unwrap().iter().sum::<i32>(), 157)
     }
 }
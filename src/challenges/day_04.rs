@@ -1,51 +1,71 @@
-use std::collections::HashSet;
+use crate::challenges::Solution;
+use crate::error::{Error, Result};
 
-fn split_to_lists(input_lines: Vec<&str>) -> Vec<(Vec<i32>, Vec<i32>)> {
+/// The first and the last section of a range, both included.
+pub type Range = (i32, i32);
+
+pub fn split_to_lists(input_lines: Vec<&str>) -> Result<Vec<(Range, Range)>> {
     input_lines
         .into_iter()
-        .map(|line| split_line(line))
+        .enumerate()
+        .map(|(index, line)| split_line(line).map_err(|err| err.on_line(index + 1)))
         .collect()
 }
 
-fn split_line(input_line: &str) -> (Vec<i32>, Vec<i32>) {
-    let mut splitted = input_line.split(",");
-    let (left, right) = (splitted.next().unwrap(), splitted.next().unwrap());
-    let mut left_splitted = left.split("-");
-    let mut right_splitted = right.split("-");
-
-    let (left_start, left_end) = (
-        left_splitted.next().unwrap().parse::<i32>().unwrap(),
-        left_splitted.next().unwrap().parse::<i32>().unwrap(),
-    );
-
-    let (right_start, right_end) = (
-        right_splitted.next().unwrap().parse::<i32>().unwrap(),
-        right_splitted.next().unwrap().parse::<i32>().unwrap(),
-    );
-    (
-        (left_start..left_end + 1).collect(),
-        (right_start..right_end + 1).collect(),
-    )
+fn parse_section(input_line: &str, start: usize, end: usize) -> Result<i32> {
+    input_line[start..end]
+        .parse::<i32>()
+        .map_err(|_| Error::parse(4, 1, start + 1, input_line, "expected a section number"))
 }
 
-fn is_sublist(left: Vec<i32>, right: Vec<i32>) -> bool {
-    left.iter().all(|item| right.contains(item)) || right.iter().all(|item| left.contains(item))
+fn parse_range(input_line: &str, start: usize, end: usize) -> Result<Range> {
+    let separator = input_line[start..end]
+        .find('-')
+        .ok_or_else(|| Error::parse(4, 1, start + 1, input_line, "expected a range like 2-4"))?
+        + start;
+    let range_start = parse_section(input_line, start, separator)?;
+    let range_end = parse_section(input_line, separator + 1, end)?;
+    if range_start > range_end {
+        return Err(Error::parse(
+            4,
+            1,
+            start + 1,
+            input_line,
+            "expected a range that does not end before it starts",
+        ));
+    }
+    Ok((range_start, range_end))
 }
 
-fn is_overlapping(left: Vec<i32>, right: Vec<i32>) -> bool {
-    let left_set: HashSet<i32> = HashSet::from_iter(left.into_iter());
-    let right_set: HashSet<i32> = HashSet::from_iter(right.into_iter());
+fn split_line(input_line: &str) -> Result<(Range, Range)> {
+    let separator = input_line.find(',').ok_or_else(|| {
+        Error::parse(
+            4,
+            1,
+            input_line.len() + 1,
+            input_line,
+            "expected two ranges separated by a comma",
+        )
+    })?;
+    Ok((
+        parse_range(input_line, 0, separator)?,
+        parse_range(input_line, separator + 1, input_line.len())?,
+    ))
+}
 
-    let intersection = left_set.intersection(&right_set);
+fn is_sublist(left: Range, right: Range) -> bool {
+    (right.0 <= left.0 && left.1 <= right.1) || (left.0 <= right.0 && right.1 <= left.1)
+}
 
-    intersection.into_iter().collect::<Vec<&i32>>().len() > 0
+fn is_overlapping(left: Range, right: Range) -> bool {
+    left.0 <= right.1 && right.0 <= left.1
 }
 
-pub fn group_sublists(input_lines: Vec<&str>) -> Vec<i32> {
-    split_to_lists(input_lines)
-        .into_iter()
+pub fn group_contained(pairs: &[(Range, Range)]) -> Vec<i32> {
+    pairs
+        .iter()
         .map(|(left, right)| {
-            if is_overlapping(left, right) {
+            if is_sublist(*left, *right) {
                 return 1;
             }
             0
@@ -53,24 +73,121 @@ pub fn group_sublists(input_lines: Vec<&str>) -> Vec<i32> {
         .collect()
 }
 
+pub fn group_sublists(pairs: &[(Range, Range)]) -> Vec<i32> {
+    pairs
+        .iter()
+        .map(|(left, right)| {
+            if is_overlapping(*left, *right) {
+                return 1;
+            }
+            0
+        })
+        .collect()
+}
+
+pub struct Day04;
+
+impl Solution for Day04 {
+    type Input = Vec<(Range, Range)>;
+    type Answer = i32;
+
+    fn day(&self) -> u8 {
+        4
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        split_to_lists(input.lines().collect())
+    }
+
+    fn part_one(&self, input: &Self::Input) -> Result<Self::Answer> {
+        Ok(group_contained(input).iter().sum())
+    }
+
+    fn part_two(&self, input: &Self::Input) -> Result<Self::Answer> {
+        Ok(group_sublists(input).iter().sum())
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
+    use super::group_contained;
+    use super::group_sublists;
     use super::is_sublist;
     use super::split_line;
+    use super::split_to_lists;
+    use super::Range;
+    use crate::error::Error;
+
+    fn example() -> Vec<(Range, Range)> {
+        split_to_lists(vec![
+            "2-4,6-8", "2-3,4-5", "5-7,7-9", "2-8,3-7", "6-6,4-6", "2-6,4-8",
+        ])
+        .unwrap()
+    }
 
     #[test]
     fn test_create_lists() {
-        assert_eq!(split_line("2-4,6-8"), (vec![2, 3, 4], vec![6, 7, 8]));
+        assert_eq!(split_line("2-4,6-8"), Ok(((2, 4), (6, 8))));
+        assert_eq!(
+            split_line("1-2147483647,1-2"),
+            Ok(((1, 2147483647), (1, 2)))
+        );
     }
 
     #[test]
-    fn test_contains() {
+    fn test_invalid_lines() {
+        assert_eq!(
+            split_to_lists(vec!["2-4,6-8", "2-x,6-8"]),
+            Err(Error::parse(
+                4,
+                2,
+                3,
+                "2-x,6-8",
+                "expected a section number"
+            ))
+        );
+        assert_eq!(
+            split_line("2-4"),
+            Err(Error::parse(
+                4,
+                1,
+                4,
+                "2-4",
+                "expected two ranges separated by a comma"
+            ))
+        );
+        assert_eq!(
+            split_line("2-4,68"),
+            Err(Error::parse(4, 1, 5, "2-4,68", "expected a range like 2-4"))
+        );
         assert_eq!(
-            is_sublist(vec![2, 3, 4, 5, 6, 7, 8], vec![3, 4, 5, 6, 7]),
-            true
+            split_line("2-4,5-3"),
+            Err(Error::parse(
+                4,
+                1,
+                5,
+                "2-4,5-3",
+                "expected a range that does not end before it starts"
+            ))
         );
-        assert_eq!(is_sublist(vec![6], vec![4, 5, 6]), true);
-        assert_eq!(is_sublist(vec![2, 3, 4, 5, 6], vec![4, 5, 6, 7, 8]), false);
+    }
+
+    #[test]
+    fn test_group_contained() {
+        assert_eq!(group_contained(&example()), vec![0, 0, 0, 1, 1, 0]);
+    }
+
+    #[test]
+    fn test_group_sublists() {
+        assert_eq!(group_sublists(&example()), vec![0, 0, 1, 1, 1, 1]);
+    }
+
+    #[test]
+    fn test_contains() {
+        assert!(is_sublist((2, 8), (3, 7)));
+        assert!(is_sublist((6, 6), (4, 6)));
+        assert!(!is_sublist((2, 6), (4, 8)));
+        assert!(is_sublist((1, 1000000000), (1, 2)));
     }
 }
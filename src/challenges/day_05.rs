@@ -1,12 +1,12 @@
+use crate::challenges::Solution;
+use crate::error::{Error, Result};
 use regex::Regex;
 use std::collections::BTreeMap;
-use std::num::ParseIntError;
-use std::ops::DerefMut;
-use std::rc::Rc;
+use std::fmt;
 use std::str::FromStr;
 
 #[derive(PartialEq, Debug, Clone)]
-struct Ship {
+pub struct Ship {
     cargos: BTreeMap<String, Vec<String>>,
 }
 
@@ -15,26 +15,54 @@ impl Ship {
         Ship { cargos }
     }
 
+    pub fn cargos(&self) -> &BTreeMap<String, Vec<String>> {
+        &self.cargos
+    }
+
+    fn stack(
+        &self,
+        move_instruction: &MoveInstruction,
+        cargo_name: &str,
+        column: usize,
+    ) -> Result<Vec<String>> {
+        self.cargos.get(cargo_name).cloned().ok_or_else(|| {
+            move_instruction.error(column, &format!("there is no stack {cargo_name}"))
+        })
+    }
+
     pub fn apply_instruction(
         &self,
         move_instruction: MoveInstruction,
         is_crate_mover_9001: bool,
-    ) -> Self {
+    ) -> Result<Self> {
         let mut cargos = self.cargos.clone();
 
-        let mut from: Vec<String> = cargos
-            .get(move_instruction.from_cargo.as_str())
-            .unwrap()
-            .clone();
-        let mut to: Vec<String> = cargos
-            .get(move_instruction.to_cargo.as_str())
-            .unwrap()
-            .clone();
+        // Columns of the stack names in `move N from A to B`.
+        let from_column = 12 + move_instruction.moved_containers.to_string().len();
+        let to_column = from_column + move_instruction.from_cargo.len() + 4;
+        let mut from: Vec<String> =
+            self.stack(&move_instruction, &move_instruction.from_cargo, from_column)?;
+        let mut to: Vec<String> =
+            self.stack(&move_instruction, &move_instruction.to_cargo, to_column)?;
+        // Both stacks are copies, so moving onto the same stack would duplicate its crates.
+        if move_instruction.from_cargo == move_instruction.to_cargo {
+            return Err(move_instruction.error(to_column, "cannot move crates onto the same stack"));
+        }
 
         let mut moved_containers: Vec<String> = Vec::new();
 
         for _ in 0..move_instruction.moved_containers {
-            let moved_container = from.pop().unwrap();
+            let moved_container = from.pop().ok_or_else(|| {
+                move_instruction.error(
+                    6,
+                    &format!(
+                        "cannot move {} crates, stack {} holds only {}",
+                        move_instruction.moved_containers,
+                        move_instruction.from_cargo,
+                        moved_containers.len()
+                    ),
+                )
+            })?;
             moved_containers.push(moved_container);
         }
         if is_crate_mover_9001 {
@@ -43,7 +71,29 @@ impl Ship {
         to.append(&mut moved_containers);
         cargos.insert(move_instruction.from_cargo, from);
         cargos.insert(move_instruction.to_cargo, to);
-        Ship::new(cargos)
+        Ok(Ship::new(cargos))
+    }
+}
+
+impl fmt::Display for Ship {
+    /// Draws the stacks in the notation of the puzzle input: every crate row padded to the full
+    /// width, bottom crates last and the stack names below, so the drawing parses back into the
+    /// same ship.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let height = self.cargos.values().map(Vec::len).max().unwrap_or(0);
+        for level in (0..height).rev() {
+            let row: Vec<String> = self
+                .cargos
+                .values()
+                .map(|stack| match stack.get(level) {
+                    Some(container) => format!("[{container}]"),
+                    None => "   ".to_string(),
+                })
+                .collect();
+            writeln!(f, "{}", row.join(" "))?;
+        }
+        let names: Vec<String> = self.cargos.keys().map(|name| format!(" {name} ")).collect();
+        write!(f, "{}", names.join(" ").trim_end())
     }
 }
 
@@ -51,7 +101,7 @@ impl Ship {
 struct ShipSetup {
     setup_lines: Vec<String>,
     cargo_names: Vec<String>,
-    instruction_lines: Vec<String>,
+    instruction_lines: Vec<(usize, String)>,
 }
 
 impl ShipSetup {
@@ -61,7 +111,8 @@ impl ShipSetup {
             let mut cargo_stack: Vec<String> = self
                 .setup_lines
                 .iter()
-                .map(|setup_line| String::from(setup_line.chars().nth((index * 4) + 1).unwrap()))
+                .filter_map(|setup_line| setup_line.chars().nth((index * 4) + 1))
+                .map(String::from)
                 .filter(|char| !char.trim().is_empty())
                 .collect();
             cargo_stack.reverse();
@@ -70,41 +121,73 @@ impl ShipSetup {
         initial_cargos
     }
 
-    fn read_instruction_lines(&self) -> Vec<String> {
+    fn read_instruction_lines(&self) -> Vec<(usize, String)> {
         self.instruction_lines.clone()
     }
 }
 
-fn create_ship_setup(input_lines: &Vec<&str>) -> ShipSetup {
-    let names_regex = Regex::new(r"(\s*(\d+)\s)+").unwrap();
-    let raw_names: &str = input_lines
-        .into_iter()
-        .map(|names| names.to_owned())
-        .filter(|line| names_regex.is_match(line))
-        .collect::<Vec<&str>>()
-        .get(0)
-        .unwrap();
+/// Checks that a line of the drawing only holds crates like `[A]` or blank cells, each above
+/// one of the `stacks` named below the drawing.
+fn check_crate_row(line: &str, stacks: usize) -> Result<()> {
+    let cells: Vec<char> = line.chars().collect();
+    for (index, cell) in cells.chunks(4).enumerate() {
+        if cell.iter().all(|c| *c == ' ') {
+            continue;
+        }
+        let start = index * 4;
+        let error = |offset: usize, message: &str| {
+            Err(Error::parse(5, 1, start + offset + 1, line, message))
+        };
+        if index >= stacks {
+            return error(0, "there is no stack below this crate");
+        }
+        if cell[0] != '[' {
+            return error(0, "expected a crate like `[A]` or an empty space");
+        }
+        match cell.get(1) {
+            Some(name) if !name.is_whitespace() && *name != '[' && *name != ']' => {}
+            _ => return error(1, "expected the name of a crate"),
+        }
+        if cell.get(2) != Some(&']') {
+            return error(2, "expected `]`");
+        }
+        if cell.get(3).is_some_and(|c| *c != ' ') {
+            return error(3, "expected a space between two crates");
+        }
+    }
+    Ok(())
+}
 
+fn create_ship_setup(input_lines: &Vec<&str>) -> Result<ShipSetup> {
+    let names_regex = Regex::new(r"^(\s*\d+)+\s*$").unwrap();
     let index = input_lines
         .iter()
         .position(|line| names_regex.is_match(line))
-        .unwrap();
+        .ok_or_else(|| {
+            Error::invalid(5, "missing the line numbering the stacks below the crates")
+        })?;
+    let raw_names: &str = input_lines[index];
     let cargo_names = string_to_cargo_names(raw_names);
+    for (line_index, line) in input_lines[0..index].iter().enumerate() {
+        check_crate_row(line, cargo_names.len()).map_err(|err| err.on_line(line_index + 1))?;
+    }
 
     let setup_lines = input_lines[0..index]
-        .into_iter()
+        .iter()
         .map(|line| String::from(line.to_owned()))
         .collect();
-    let instruction_lines = input_lines[index + 1..]
-        .into_iter()
-        .filter(|line| !line.trim().is_empty())
-        .map(|line| String::from(line.to_owned()))
+    let instruction_lines = input_lines
+        .iter()
+        .enumerate()
+        .skip(index + 1)
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(line_index, line)| (line_index + 1, String::from(line.to_owned())))
         .collect();
-    ShipSetup {
+    Ok(ShipSetup {
         setup_lines,
         cargo_names,
         instruction_lines,
-    }
+    })
 }
 
 fn string_to_cargo_names(input: &str) -> Vec<String> {
@@ -115,64 +198,215 @@ fn string_to_cargo_names(input: &str) -> Vec<String> {
         .collect()
 }
 
-pub fn apply_instructions(input_lines: Vec<&str>, is_crate_mover_9001: bool) -> String {
-    let ship_setup = create_ship_setup(&input_lines);
-    let move_instructions = ship_setup.read_instruction_lines();
-    let initial_cargos = ship_setup.read_initial_cargos();
-    let initial_ship = Ship::new(initial_cargos);
-    let output: Ship = move_instructions
+fn parse_ship(input_lines: &Vec<&str>) -> Result<(Ship, Vec<MoveInstruction>)> {
+    let ship_setup = create_ship_setup(input_lines)?;
+    let move_instructions = ship_setup
+        .read_instruction_lines()
         .into_iter()
-        .map(|instruction| MoveInstruction::from_str(&instruction).unwrap())
-        .fold(initial_ship, |acc, instruction| {
-            acc.apply_instruction(instruction, is_crate_mover_9001)
+        .map(|(line_number, instruction)| {
+            let move_instruction =
+                MoveInstruction::from_str(&instruction).map_err(|err| err.on_line(line_number))?;
+            Ok(MoveInstruction {
+                line: line_number,
+                ..move_instruction
+            })
+        })
+        .collect::<Result<Vec<MoveInstruction>>>()?;
+    Ok((
+        Ship::new(ship_setup.read_initial_cargos()),
+        move_instructions,
+    ))
+}
+
+/// A single move of the crane, with the ship before and after it.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Stage {
+    pub instruction: MoveInstruction,
+    pub before: Ship,
+    pub after: Ship,
+}
+
+impl fmt::Display for Stage {
+    /// Prints the instruction above the drawings before and after it, side by side.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let before = self.before.to_string();
+        let after = self.after.to_string();
+        let before: Vec<&str> = before.lines().collect();
+        let after: Vec<&str> = after.lines().collect();
+        let width = before.iter().map(|line| line.len()).max().unwrap_or(0);
+        let height = before.len().max(after.len());
+        let padded = |lines: &[&str], row: usize| -> String {
+            let offset = height - lines.len();
+            row.checked_sub(offset)
+                .map(|row| lines[row].to_string())
+                .unwrap_or_default()
+        };
+
+        writeln!(f, "{}", self.instruction)?;
+        for row in 0..height {
+            let separator = if row + 1 == height { " -> " } else { "    " };
+            let line = format!(
+                "{:<width$}{separator}{}",
+                padded(&before, row),
+                padded(&after, row)
+            );
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+/// Applies the instructions one by one, keeping every intermediate ship.
+pub fn trace(
+    initial_ship: &Ship,
+    move_instructions: &[MoveInstruction],
+    is_crate_mover_9001: bool,
+) -> Result<Vec<Stage>> {
+    let mut stages: Vec<Stage> = Vec::with_capacity(move_instructions.len());
+    let mut ship = initial_ship.clone();
+    for instruction in move_instructions.iter() {
+        let after = ship.apply_instruction(instruction.clone(), is_crate_mover_9001)?;
+        stages.push(Stage {
+            instruction: instruction.clone(),
+            before: ship,
+            after: after.clone(),
         });
-    output
+        ship = after;
+    }
+    Ok(stages)
+}
+
+/// Parses the input and traces every move of the crane.
+pub fn trace_instructions(input_lines: Vec<&str>, is_crate_mover_9001: bool) -> Result<Vec<Stage>> {
+    let (initial_ship, move_instructions) = parse_ship(&input_lines)?;
+    trace(&initial_ship, &move_instructions, is_crate_mover_9001)
+}
+
+fn top_crates(
+    initial_ship: &Ship,
+    move_instructions: &[MoveInstruction],
+    is_crate_mover_9001: bool,
+) -> Result<String> {
+    let output: Ship = move_instructions
+        .iter()
+        .try_fold(initial_ship.clone(), |acc, instruction| {
+            acc.apply_instruction(instruction.clone(), is_crate_mover_9001)
+        })?;
+    Ok(output
         .cargos
         .values()
-        .into_iter()
-        .map(|cargo| cargo.get(cargo.len() - 1).unwrap().to_string())
-        .collect()
+        .filter_map(|cargo| cargo.last())
+        .map(|top| top.to_string())
+        .collect())
 }
 
-#[derive(PartialEq, Debug)]
-struct MoveInstruction {
-    moved_containers: i32,
-    from_cargo: String,
-    to_cargo: String,
+/// Writes a ship and its instructions back in the format of the puzzle input.
+pub fn render_input(ship: &Ship, move_instructions: &[MoveInstruction]) -> String {
+    let instructions: Vec<String> = move_instructions
+        .iter()
+        .map(|instruction| instruction.to_string())
+        .collect();
+    format!("{ship}\n\n{}", instructions.join("\n"))
 }
 
-impl FromStr for MoveInstruction {
-    type Err = ParseIntError;
+pub fn apply_instructions(input_lines: Vec<&str>, is_crate_mover_9001: bool) -> Result<String> {
+    let (initial_ship, move_instructions) = parse_ship(&input_lines)?;
+    top_crates(&initial_ship, &move_instructions, is_crate_mover_9001)
+}
+
+pub struct Day05;
+
+impl Solution for Day05 {
+    type Input = (Ship, Vec<MoveInstruction>);
+    type Answer = String;
+
+    fn day(&self) -> u8 {
+        5
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse_ship(&input.lines().collect())
+    }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let instruction_regex: Regex = Regex::new(r"move (\d+) from (\d+) to (\d+)").unwrap();
-        let caps = instruction_regex.captures(s).unwrap();
-        let number_moved = caps.get(1).unwrap().as_str().parse::<i32>().unwrap();
+    fn part_one(&self, (ship, move_instructions): &Self::Input) -> Result<Self::Answer> {
+        top_crates(ship, move_instructions, false)
+    }
+
+    fn part_two(&self, (ship, move_instructions): &Self::Input) -> Result<Self::Answer> {
+        top_crates(ship, move_instructions, true)
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct MoveInstruction {
+    pub moved_containers: i32,
+    pub from_cargo: String,
+    pub to_cargo: String,
+    /// Line of the puzzle input the instruction was read from, 1 for a single line.
+    pub line: usize,
+}
+
+impl MoveInstruction {
+    /// An error pointing at a column of this instruction on its line of the input.
+    fn error(&self, column: usize, message: &str) -> Error {
+        Error::parse(5, self.line, column, &self.to_string(), message)
+    }
+}
+
+impl FromStr for MoveInstruction {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let instruction_regex: Regex = Regex::new(r"^move (\d+) from (\d+) to (\d+)$").unwrap();
+        let caps = instruction_regex.captures(s).ok_or_else(|| {
+            Error::parse(
+                5,
+                1,
+                1,
+                s,
+                "expected an instruction like `move 1 from 2 to 3`",
+            )
+        })?;
+        let moved = caps.get(1).unwrap();
+        let number_moved = moved.as_str().parse::<i32>().map_err(|_| {
+            Error::parse(5, 1, moved.start() + 1, s, "number of crates is too large")
+        })?;
         let from_cargo = caps.get(2).unwrap().as_str().to_string();
         let to_cargo = caps.get(3).unwrap().as_str().to_string();
         Ok(Self {
             moved_containers: number_moved,
             from_cargo,
             to_cargo,
+            line: 1,
         })
     }
 }
 
+impl fmt::Display for MoveInstruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "move {} from {} to {}",
+            self.moved_containers, self.from_cargo, self.to_cargo
+        )
+    }
+}
+
+#[cfg(test)]
 fn string_to_vec_of_strings(input: &str) -> Vec<String> {
-    input
-        .chars()
-        .into_iter()
-        .map(|char| char.to_string())
-        .collect()
+    input.chars().map(|char| char.to_string()).collect()
 }
 
 #[cfg(test)]
 mod tests {
-    use super::create_ship_setup;
     use super::string_to_cargo_names;
     use super::string_to_vec_of_strings;
     use super::ShipSetup;
-    use crate::challenges::day_05::{apply_instructions, MoveInstruction, Ship};
+    use super::{create_ship_setup, parse_ship, render_input, trace};
+    use crate::challenges::day_05::{
+        apply_instructions, trace_instructions, MoveInstruction, Ship,
+    };
+    use crate::error::Error;
     use std::collections::BTreeMap;
     use std::str::FromStr;
 
@@ -185,6 +419,7 @@ mod tests {
                 moved_containers: 1,
                 to_cargo: String::from("5"),
                 from_cargo: String::from("3"),
+                line: 1,
             }
         );
     }
@@ -210,13 +445,13 @@ mod tests {
             String::from("[Z] [D] [P]"),
         ];
         let expected_move_instructions = vec![
-            String::from("move 1 from 2 to 1"),
-            String::from("move 3 from 1 to 3"),
-            String::from("move 2 from 2 to 1"),
-            String::from("move 1 from 1 to 2"),
+            (6, String::from("move 1 from 2 to 1")),
+            (7, String::from("move 3 from 1 to 3")),
+            (8, String::from("move 2 from 2 to 1")),
+            (9, String::from("move 1 from 1 to 2")),
         ];
 
-        let ship_setup = create_ship_setup(&input_lines);
+        let ship_setup = create_ship_setup(&input_lines).unwrap();
         assert_eq!(
             ship_setup,
             ShipSetup {
@@ -247,7 +482,7 @@ mod tests {
             ("3".to_string(), string_to_vec_of_strings("P")),
         ]);
 
-        let ship_setup = create_ship_setup(&input_lines);
+        let ship_setup = create_ship_setup(&input_lines).unwrap();
         assert_eq!(ship_setup.read_initial_cargos(), expected_cargo_names)
     }
 
@@ -265,7 +500,10 @@ mod tests {
             "move 1 from 1 to 2",
         ];
 
-        assert_eq!(apply_instructions(input_lines, false), "CMZ");
+        assert_eq!(
+            apply_instructions(input_lines, false),
+            Ok("CMZ".to_string())
+        );
     }
 
     #[test]
@@ -282,7 +520,114 @@ mod tests {
             "move 1 from 1 to 2",
         ];
 
-        assert_eq!(apply_instructions(input_lines, true), "MCD");
+        assert_eq!(apply_instructions(input_lines, true), Ok("MCD".to_string()));
+    }
+
+    #[test]
+    fn test_invalid_instruction() {
+        let input_lines = vec![
+            "[Z] [M] [P]",
+            " 1   2   3",
+            "",
+            "move 1 from 2 to 1",
+            "move x from 1 to 3",
+        ];
+
+        assert_eq!(
+            apply_instructions(input_lines, false),
+            Err(Error::parse(
+                5,
+                5,
+                1,
+                "move x from 1 to 3",
+                "expected an instruction like `move 1 from 2 to 3`"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_move_from_empty_stack() {
+        let input_lines = vec!["[Z]    ", " 1   2", "", "move 2 from 1 to 2"];
+
+        assert_eq!(
+            apply_instructions(input_lines, false),
+            Err(Error::parse(
+                5,
+                4,
+                6,
+                "move 2 from 1 to 2",
+                "cannot move 2 crates, stack 1 holds only 1"
+            ))
+        );
+
+        let input_lines = vec![
+            "[Z]    ",
+            " 1   2",
+            "",
+            "move 1 from 1 to 2",
+            "move 1 from 2 to 9",
+        ];
+        assert_eq!(
+            apply_instructions(input_lines, false),
+            Err(Error::parse(
+                5,
+                5,
+                18,
+                "move 1 from 2 to 9",
+                "there is no stack 9"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_move_onto_the_same_stack() {
+        let input_lines = vec!["[B]", "[A]", " 1 ", "", "move 2 from 1 to 1"];
+        assert_eq!(
+            apply_instructions(input_lines, true),
+            Err(Error::parse(
+                5,
+                5,
+                18,
+                "move 2 from 1 to 1",
+                "cannot move crates onto the same stack"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_invalid_drawing() {
+        let error =
+            |line, column, text: &str, message| Err(Error::parse(5, line, column, text, message));
+        assert_eq!(
+            apply_instructions(vec!["garbage!", " 1   2", ""], false),
+            error(
+                1,
+                1,
+                "garbage!",
+                "expected a crate like `[A]` or an empty space"
+            )
+        );
+        assert_eq!(
+            apply_instructions(vec!["    [C]", "[A] [B", " 1   2", ""], false),
+            error(2, 7, "[A] [B", "expected `]`")
+        );
+        assert_eq!(
+            apply_instructions(vec!["[A]_[B]", " 1   2", ""], false),
+            error(1, 4, "[A]_[B]", "expected a space between two crates")
+        );
+        assert_eq!(
+            apply_instructions(vec!["[A] [B] [C]", " 1   2", ""], false),
+            error(1, 9, "[A] [B] [C]", "there is no stack below this crate")
+        );
+        assert_eq!(
+            apply_instructions(vec!["[A]", " 1", "", "move 1 from 1 to 1 please"], false),
+            error(
+                4,
+                1,
+                "move 1 from 1 to 1 please",
+                "expected an instruction like `move 1 from 2 to 3`"
+            )
+        );
     }
 
     #[test]
@@ -305,10 +650,12 @@ mod tests {
         ]);
 
         let ship = Ship::new(cargos);
-        let ship_new = ship.apply_instruction(
-            MoveInstruction::from_str("move 1 from 2 to 1").unwrap(),
-            false,
-        );
+        let ship_new = ship
+            .apply_instruction(
+                MoveInstruction::from_str("move 1 from 2 to 1").unwrap(),
+                false,
+            )
+            .unwrap();
 
         let expected_cargos = BTreeMap::from([
             (
@@ -321,4 +668,69 @@ mod tests {
 
         assert_eq!(ship_new, Ship::new(expected_cargos));
     }
+
+    #[test]
+    fn test_display() {
+        let cargos = BTreeMap::from([
+            ("1".to_string(), string_to_vec_of_strings("ZN")),
+            ("2".to_string(), string_to_vec_of_strings("MCD")),
+            ("3".to_string(), Vec::new()),
+        ]);
+
+        assert_eq!(
+            Ship::new(cargos).to_string(),
+            "    [D]    
+[N] [C]    
+[Z] [M]    
+ 1   2   3"
+        );
+    }
+
+    #[test]
+    fn test_render_input_round_trip() {
+        let input = include_str!("../../examples/day_05_sample.txt");
+        let (ship, move_instructions) = parse_ship(&input.lines().collect()).unwrap();
+
+        assert_eq!(render_input(&ship, &move_instructions), input.trim_end());
+
+        for stage in trace(&ship, &move_instructions, true).unwrap() {
+            let drawing = format!("{}\n", stage.after);
+            let (parsed, _) = parse_ship(&drawing.lines().collect()).unwrap();
+            assert_eq!(parsed, stage.after);
+        }
+    }
+
+    #[test]
+    fn test_trace() {
+        let input_lines = vec![
+            "    [D]    ",
+            "[N] [C]    ",
+            "[Z] [M] [P]",
+            " 1   2   3",
+            "",
+            "move 1 from 2 to 1",
+            "move 3 from 1 to 3",
+        ];
+
+        let stages = trace_instructions(input_lines, false).unwrap();
+        assert_eq!(stages.len(), 2);
+        assert_eq!(stages[0].after, stages[1].before);
+        assert_eq!(
+            stages[0].to_string(),
+            "move 1 from 2 to 1
+    [D]        [D]
+[N] [C]        [N] [C]
+[Z] [M] [P]    [Z] [M] [P]
+ 1   2   3  ->  1   2   3
+"
+        );
+        assert_eq!(
+            stages[1].after.to_string(),
+            "        [Z]
+        [N]
+    [C] [D]
+    [M] [P]
+ 1   2   3"
+        );
+    }
 }
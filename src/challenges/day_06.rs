@@ -1,23 +1,46 @@
+use crate::challenges::Solution;
+use crate::error::{Error, Result};
 use std::collections::HashSet;
 
 fn is_distinct(sequence: &str) -> bool {
     let mut uniq = HashSet::new();
-    sequence.chars().into_iter().all(move |x| uniq.insert(x))
+    sequence.chars().all(move |x| uniq.insert(x))
 }
 
 pub fn find_first_distinct_sequence(
     sequence: String,
     required_sequence_length: usize,
 ) -> Option<i32> {
-    for (i, elem) in sequence.chars().enumerate() {
-        if i + required_sequence_length > sequence.len() {
-            return None;
-        }
-        if is_distinct(&sequence[i..i + required_sequence_length]) {
-            return Some((i + required_sequence_length) as i32);
-        }
+    let chars: Vec<char> = sequence.chars().collect();
+    chars
+        .windows(required_sequence_length)
+        .position(|window| is_distinct(&window.iter().collect::<String>()))
+        .map(|i| (i + required_sequence_length) as i32)
+}
+
+pub struct Day06;
+
+impl Solution for Day06 {
+    type Input = String;
+    type Answer = i32;
+
+    fn day(&self) -> u8 {
+        6
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        Ok(input.trim().to_string())
+    }
+
+    fn part_one(&self, input: &Self::Input) -> Result<Self::Answer> {
+        find_first_distinct_sequence(input.clone(), 4)
+            .ok_or_else(|| Error::invalid(6, "no start-of-packet marker in the datastream"))
+    }
+
+    fn part_two(&self, input: &Self::Input) -> Result<Self::Answer> {
+        find_first_distinct_sequence(input.clone(), 14)
+            .ok_or_else(|| Error::invalid(6, "no start-of-message marker in the datastream"))
     }
-    None
 }
 
 #[cfg(test)]
@@ -27,8 +50,8 @@ mod tests {
 
     #[test]
     fn test_is_distinct() {
-        assert_eq!(is_distinct("abcdef"), true);
-        assert_eq!(is_distinct("abcdea"), false);
+        assert!(is_distinct("abcdef"));
+        assert!(!is_distinct("abcdea"));
     }
 
     #[test]
@@ -1,175 +1,347 @@
-use regex::Regex;
-use std::collections::{BTreeMap, HashMap};
-use std::iter::Map;
+use crate::challenges::Solution;
+use crate::error::{Error, Result};
+use filesystem::{DirectoryId, FileSystem};
+use std::collections::BTreeMap;
+use transcript::{Line, Target};
+use validate::{Checker, Diagnostic, Severity};
 
-struct Terminal {
-    accumulated_lines: String,
-    current_directory: String,
+pub mod diff;
+pub mod filesystem;
+pub mod json;
+pub mod materialise;
+pub mod query;
+pub mod render;
+pub mod scan;
+pub mod shell;
+pub mod transcript;
+pub mod validate;
+
+/// Replays a terminal session, building up the file system it explores.
+pub struct Terminal {
+    filesystem: FileSystem,
+    current_directory: DirectoryId,
+}
+
+impl Default for Terminal {
+    fn default() -> Self {
+        Terminal::new()
+    }
 }
 
 impl Terminal {
-    fn new() -> Self {
+    pub fn new() -> Self {
         Terminal {
-            accumulated_lines: String::from("root/"),
-            current_directory: String::from("root/"),
+            filesystem: FileSystem::new(),
+            current_directory: FileSystem::ROOT,
         }
     }
 
-    fn change_up(&self) -> Terminal {
-        let mut directories: Vec<&str> = self
-            .current_directory
-            .split("/").collect();
-        directories.pop();
-        let super_directory: String = directories.into_iter().map(|dir| dir.to_string()).collect::<Vec<String>>().join("/");
+    /// Starts at the root of an already explored file system.
+    pub fn with_filesystem(filesystem: FileSystem) -> Self {
         Terminal {
-            accumulated_lines: format!(
-                "{}\n{}",
-                self.accumulated_lines,
-                self.current_directory.clone()
-            ),
-            current_directory: format!("{super_directory}/"),
+            filesystem,
+            current_directory: FileSystem::ROOT,
         }
     }
 
-    fn add_file(&self, file_size: &str) -> Terminal {
-        let prefix = if self.accumulated_lines.ends_with("/") {
-            "|"
-        } else {
-            ","
-        };
-        let accumulated_lines = format!("{}{prefix}{file_size}", self.accumulated_lines);
+    pub fn filesystem(&self) -> &FileSystem {
+        &self.filesystem
+    }
+
+    pub fn into_filesystem(self) -> FileSystem {
+        self.filesystem
+    }
+
+    pub fn current_directory(&self) -> DirectoryId {
+        self.current_directory
+    }
+
+    pub fn change_up(self) -> Terminal {
+        let current_directory = self
+            .filesystem
+            .directory(self.current_directory)
+            .parent()
+            .unwrap_or(FileSystem::ROOT);
         Terminal {
-            accumulated_lines,
-            current_directory: self.current_directory.clone(),
+            current_directory,
+            ..self
         }
     }
 
-    fn change_directory(&self, directory_name: &str) -> Terminal {
-        let current_directory = format!("{}{directory_name}/", self.current_directory.clone());
+    pub fn add_file(mut self, file_name: &str, file_size: u64) -> Result<Terminal> {
+        self.filesystem
+            .add_file(self.current_directory, file_name, file_size)?;
+        Ok(self)
+    }
+
+    pub fn change_root(self) -> Terminal {
         Terminal {
-            accumulated_lines: format!("{}\n{}", self.accumulated_lines, current_directory.clone()),
+            current_directory: FileSystem::ROOT,
+            ..self
+        }
+    }
+
+    /// Records a directory listed by `ls` without entering it.
+    pub fn add_directory(mut self, directory_name: &str) -> Result<Terminal> {
+        self.filesystem
+            .add_directory(self.current_directory, directory_name)?;
+        Ok(self)
+    }
+
+    pub fn execute(self, line: Line) -> Result<Terminal> {
+        Ok(match line {
+            Line::ChangeDirectory(Target::Root) => self.change_root(),
+            Line::ChangeDirectory(Target::Parent) => self.change_up(),
+            Line::ChangeDirectory(Target::Child(name)) => self.change_directory(&name)?,
+            Line::List => self,
+            Line::Directory(name) => self.add_directory(&name)?,
+            Line::File { name, size } => self.add_file(&name, size)?,
+        })
+    }
+
+    pub fn change_directory(mut self, directory_name: &str) -> Result<Terminal> {
+        let current_directory = self
+            .filesystem
+            .add_directory(self.current_directory, directory_name)?;
+        Ok(Terminal {
             current_directory,
+            ..self
+        })
+    }
+}
+
+fn replay(input_lines: Vec<&str>) -> Result<(FileSystem, Vec<Diagnostic>)> {
+    let mut terminal = Terminal::new();
+    let mut checker = Checker::default();
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+    let mut listing = false;
+    for (index, text) in input_lines.into_iter().enumerate() {
+        if text.trim().is_empty() {
+            continue;
+        }
+        let line: Line = text.parse().map_err(|err: Error| err.on_line(index + 1))?;
+        if line.is_output() && !listing {
+            return Err(Error::parse(
+                7,
+                index + 1,
+                1,
+                text,
+                "output without a preceding `$ ls`",
+            ));
         }
+        diagnostics.extend(checker.check(
+            terminal.filesystem(),
+            terminal.current_directory(),
+            &line,
+            index + 1,
+            text,
+        ));
+        listing = line == Line::List || (listing && line.is_output());
+        terminal = terminal.execute(line).map_err(|err| match err {
+            Error::Invalid { message, .. } => Error::parse(7, index + 1, 1, text, &message),
+            other => other,
+        })?;
     }
+    Ok((terminal.into_filesystem(), diagnostics))
 }
 
-fn parse_input(input_lines: Vec<&str>) -> String {
-    let file_regex = Regex::new(r"(\d+) (\w+(\.\w+)*)").unwrap();
-    let change_directory_regex = Regex::new(r"\$\s*cd\s*(\w+)").unwrap();
-    let initial_terminal = Terminal::new();
-    input_lines
+/// Replays a terminal transcript and returns the file system it explored.
+///
+/// Fails on the first line that contradicts an earlier one, warnings are ignored.
+pub fn parse_input(input_lines: Vec<&str>) -> Result<FileSystem> {
+    let (filesystem, diagnostics) = replay(input_lines)?;
+    match diagnostics
         .into_iter()
-        .fold(initial_terminal, |curr, command| {
-            if file_regex.is_match(command) {
-                let caps = file_regex.captures(command).unwrap();
-                let file_size = caps.get(1).unwrap().as_str();
-                return curr.add_file(file_size);
-            }
-            if command == "$ cd .." {
-                return curr.change_up();
-            }
-            if change_directory_regex.is_match(command) {
-                let caps = change_directory_regex.captures(command).unwrap();
-                let directory = caps.get(1).unwrap().as_str();
-                return curr.change_directory(directory);
-            }
-            curr
-        })
-        .accumulated_lines
-}
-
-fn count_size(input_lines: Vec<&str>) -> HashMap<String, u32> {
-    let mut map: HashMap<String, u32> = HashMap::new();
-    for line in input_lines.into_iter() {
-        let mut directories = line.split("|");
-        let directory_names = directories.next().unwrap();
-        if let Some(sizes) = directories.next() {
-            for directory_name in extract_directory_names(directory_names) {
-                let size = map.entry(directory_name).or_insert(0);
-                *size += sum_of_strings(sizes);
-            }
-        }
+        .find(|diagnostic| diagnostic.severity == Severity::Error)
+    {
+        Some(diagnostic) => Err(diagnostic.into_error()),
+        None => Ok(filesystem),
     }
-    map
 }
 
-fn sum_of_strings(sizes: &str) -> u32 {
-    sizes
-        .split(",")
-        .filter(|split| !split.is_empty())
-        .map(|size| size.parse::<u32>().unwrap())
-        .sum()
+/// Reads either a terminal transcript or a file system exported as JSON.
+pub fn parse(input: &str) -> Result<FileSystem> {
+    if input.trim_start().starts_with('{') {
+        json::import(input)
+    } else {
+        parse_input(input.lines().collect())
+    }
 }
 
-fn extract_directory_names(input: &str) -> Vec<String> {
-    input
-        .split("/")
-        .map(|directory| directory.to_string())
-        .filter(|split| !split.is_empty())
-        .collect()
+/// Runs the consistency checks over a transcript, in the order of the lines.
+pub fn validate(input_lines: Vec<&str>) -> Result<Vec<Diagnostic>> {
+    Ok(replay(input_lines)?.1)
 }
 
 fn filter_directories_with_maximum(
-    map: HashMap<String, u32>,
-    maximum: u32,
-) -> HashMap<String, u32> {
+    map: BTreeMap<DirectoryId, u64>,
+    maximum: u64,
+) -> BTreeMap<DirectoryId, u64> {
     map.into_iter()
-        .filter(|(key, value)| value.clone() <= maximum)
+        .filter(|(_, value)| *value <= maximum)
         .collect()
 }
 
-pub fn assembly(input_lines: Vec<&str>) -> u32 {
-    let input = parse_input(input_lines);
-    let map = count_size(input.lines().collect());
-    println!("map {:?}", map);
+fn sum_of_small_directories(filesystem: &FileSystem) -> Result<u64> {
+    let map = filesystem.sizes();
     filter_directories_with_maximum(map, 100000)
-        .into_iter()
-        .map(|(k, v)| v)
-        .sum()
+        .into_values()
+        .try_fold(0u64, |sum, size| sum.checked_add(size))
+        .ok_or_else(|| Error::invalid(7, "the sum of the directory sizes does not fit in 64 bits"))
+}
+
+pub fn assembly(input_lines: Vec<&str>) -> Result<u64> {
+    sum_of_small_directories(&parse_input(input_lines)?)
+}
+
+/// Size of the disk and the free space an update needs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DiskLimits {
+    pub capacity: u64,
+    pub required_free: u64,
+}
+
+impl Default for DiskLimits {
+    fn default() -> Self {
+        DiskLimits {
+            capacity: 70000000,
+            required_free: 30000000,
+        }
+    }
+}
+
+/// Size of the smallest directory whose deletion leaves at least the required free space.
+fn smallest_directory_to_delete(filesystem: &FileSystem, limits: DiskLimits) -> Result<u64> {
+    let used = filesystem.size(FileSystem::ROOT);
+    let free = limits
+        .capacity
+        .checked_sub(used)
+        .ok_or_else(|| Error::invalid(7, "the files do not fit on the disk"))?;
+    let missing = limits.required_free.saturating_sub(free);
+    filesystem
+        .sizes()
+        .into_values()
+        .filter(|size| *size >= missing)
+        .min()
+        .ok_or_else(|| Error::invalid(7, "no directory frees enough space"))
+}
+
+pub fn free_space(input_lines: Vec<&str>, limits: DiskLimits) -> Result<u64> {
+    smallest_directory_to_delete(&parse_input(input_lines)?, limits)
+}
+
+/// File system of the puzzle example, shared by the tests of all day 7 modules.
+#[cfg(test)]
+pub fn example() -> FileSystem {
+    parse_input(
+        include_str!("../../examples/day_07_sample.txt")
+            .lines()
+            .collect(),
+    )
+    .unwrap()
+}
+
+/// Directories `/d/d/.../d` nested `depth` levels deep, with a file `f` of size 7 at the bottom,
+/// for the tests that must not recurse.
+#[cfg(test)]
+pub fn deep_tree(depth: usize) -> FileSystem {
+    let mut filesystem = FileSystem::new();
+    let bottom = (0..depth).fold(FileSystem::ROOT, |parent, _| {
+        filesystem.add_directory(parent, "d").unwrap()
+    });
+    filesystem.add_file(bottom, "f", 7).unwrap();
+    filesystem
+}
+
+/// Day 7, part two looks for space on a disk with the given limits.
+#[derive(Default)]
+pub struct Day07 {
+    pub limits: DiskLimits,
+}
+
+impl Solution for Day07 {
+    type Input = FileSystem;
+    type Answer = u64;
+
+    fn day(&self) -> u8 {
+        7
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        parse(input)
+    }
+
+    fn part_one(&self, input: &Self::Input) -> Result<Self::Answer> {
+        sum_of_small_directories(input)
+    }
+
+    fn part_two(&self, input: &Self::Input) -> Result<Self::Answer> {
+        smallest_directory_to_delete(input, self.limits)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::assembly;
-    use super::count_size;
+    use super::example;
+    use super::filesystem::{File, FileSystem};
     use super::parse_input;
-    use super::sum_of_strings;
+    use super::validate::{Diagnostic, Severity};
+    use super::{free_space, validate, Day07, DiskLimits};
+    use crate::challenges::{Part, Puzzle};
+    use crate::error::Error;
     use std::collections::HashMap;
 
+    const EXAMPLE: &str = include_str!("../../examples/day_07_sample.txt");
+
     #[test]
-    fn test_assembly() {
-        let input = "$ cd /
-$ ls
-dir a
-14848514 b.txt
-8504156 c.dat
-dir d
-$ cd a
-$ ls
-dir e
-29116 f
-2557 g
-62596 h.lst
-$ cd e
-$ ls
-584 i
-$ cd ..
-$ cd ..
-$ cd d
-$ ls
-4060174 j
-8033020 d.log
-5626152 d.ext
-7214296 k";
+    fn test_free_space() {
+        let lines = || EXAMPLE.lines().collect();
+
+        assert_eq!(free_space(lines(), DiskLimits::default()), Ok(24933642));
+        assert_eq!(
+            free_space(
+                lines(),
+                DiskLimits {
+                    capacity: 48381165,
+                    required_free: 90000
+                }
+            ),
+            Ok(94853)
+        );
+        assert_eq!(
+            free_space(
+                lines(),
+                DiskLimits {
+                    capacity: 40000000,
+                    required_free: 0
+                }
+            ),
+            Err(Error::invalid(7, "the files do not fit on the disk"))
+        );
+        assert_eq!(
+            free_space(
+                lines(),
+                DiskLimits {
+                    capacity: 70000000,
+                    required_free: 80000000
+                }
+            ),
+            Err(Error::invalid(7, "no directory frees enough space"))
+        );
 
-        assert_eq!(assembly(input.lines().collect()), 95437);
+        let puzzle = Day07 {
+            limits: DiskLimits {
+                capacity: 48381165,
+                required_free: 90000,
+            },
+        };
+        assert_eq!(puzzle.solve(EXAMPLE, Part::Two), Ok("94853".to_string()));
     }
 
     #[test]
-    fn test_sum_of_strings() {
-        assert_eq!(sum_of_strings(""), 0);
-        assert_eq!(sum_of_strings("1,"), 1);
-        assert_eq!(sum_of_strings("2,3"), 5);
+    fn test_assembly() {
+        assert_eq!(assembly(EXAMPLE.lines().collect()), Ok(95437));
     }
 
     #[test]
@@ -200,38 +372,260 @@ $ ls
 
         let splitted: Vec<&str> = input.lines().collect();
 
-        let result = parse_input(splitted);
+        let result = parse_input(splitted).unwrap();
+
+        let paths: Vec<String> = result
+            .walk()
+            .into_iter()
+            .map(|id| result.path(id))
+            .collect();
+        assert_eq!(paths, vec!["/", "/abab", "/abab/ef", "/d"]);
+
+        let ef = result.lookup("/abab/ef").unwrap();
+        assert_eq!(
+            result.directory(ef).files(),
+            &[File {
+                name: "i".to_string(),
+                size: 584
+            }]
+        );
+        assert_eq!(result.directory(FileSystem::ROOT).files().len(), 2);
+        assert_eq!(result.size(FileSystem::ROOT), 48381165);
+    }
+
+    #[test]
+    fn test_parse_full_transcript() {
+        let input = "$ cd /
+$ ls
+dir never-entered
+dir a.b
+$ cd a.b
+$ ls
+dir c-d
+10 x
+$ cd c-d
+$ cd /
+$ ls
+20 y";
+
+        let filesystem = parse_input(input.lines().collect()).unwrap();
+        let paths: Vec<String> = filesystem
+            .walk()
+            .into_iter()
+            .map(|id| filesystem.path(id))
+            .collect();
+        assert_eq!(paths, vec!["/", "/a.b", "/a.b/c-d", "/never-entered"]);
+        assert_eq!(filesystem.directory(FileSystem::ROOT).files().len(), 1);
+        assert_eq!(filesystem.size(FileSystem::ROOT), 30);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            parse_input(vec!["$ cd /", "$ pwd"]),
+            Err(Error::parse(7, 2, 3, "$ pwd", "unknown command `pwd`"))
+        );
+        assert_eq!(
+            parse_input(vec!["$ cd /", "$ ls", "1 a", "$ cd ..", "2 b"]),
+            Err(Error::parse(
+                7,
+                5,
+                1,
+                "2 b",
+                "output without a preceding `$ ls`"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let input = "$ cd ..
+$ ls
+dir a
+10 x
+$ cd a
+$ ls
+$ cd /
+$ ls
+10 x
+20 x";
+
+        let diagnostic = |severity, line, text: &str, message: &str| Diagnostic {
+            severity,
+            line,
+            text: text.to_string(),
+            message: message.to_string(),
+        };
+        assert_eq!(
+            validate(input.lines().collect()),
+            Ok(vec![
+                diagnostic(
+                    Severity::Warning,
+                    1,
+                    "$ cd ..",
+                    "`cd ..` at the root stays at the root"
+                ),
+                diagnostic(
+                    Severity::Warning,
+                    8,
+                    "$ ls",
+                    "/ was already listed on line 2"
+                ),
+                diagnostic(
+                    Severity::Error,
+                    10,
+                    "20 x",
+                    "/x has size 20, but size 10 on line 4"
+                ),
+            ])
+        );
+        assert_eq!(
+            parse_input(input.lines().collect()),
+            Err(Error::parse(
+                7,
+                10,
+                1,
+                "20 x",
+                "/x has size 20, but size 10 on line 4"
+            ))
+        );
+        assert_eq!(validate(EXAMPLE.lines().collect()), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_file_and_directory_with_the_same_name() {
+        assert_eq!(
+            parse_input(vec!["$ cd /", "$ ls", "dir x", "5 x"]),
+            Err(Error::parse(
+                7,
+                4,
+                1,
+                "5 x",
+                "/x is a directory, not a file"
+            ))
+        );
+        assert_eq!(
+            parse_input(vec!["$ cd /", "$ ls", "5 x", "$ cd x"]),
+            Err(Error::parse(
+                7,
+                4,
+                1,
+                "$ cd x",
+                "/x is a file on line 3, not a directory"
+            ))
+        );
+    }
+
+    /// Directories `/d0/d1/.../d99`, each holding a file of one TiB.
+    fn terabyte_transcript() -> String {
+        let mut lines = vec![
+            "$ cd /".to_string(),
+            "$ ls".to_string(),
+            "dir d0".to_string(),
+        ];
+        lines.push("1 root.txt".to_string());
+        for depth in 0..100 {
+            lines.push(format!("$ cd d{depth}"));
+            lines.push("$ ls".to_string());
+            lines.push(format!("{} data.bin", 1u64 << 40));
+            if depth < 99 {
+                lines.push(format!("dir d{}", depth + 1));
+            }
+        }
+        lines.join("\n")
+    }
+
+    #[test]
+    fn test_terabyte_sizes() {
+        let input = terabyte_transcript();
+        let filesystem = parse_input(input.lines().collect()).unwrap();
 
-        let expected = "root/|14848514,8504156
-root/abab/|29116,2557,62596
-root/abab/ef/|584
-root/abab/ef/
-root/abab/
-root/d/|4060174,8033020,5626152,7214296";
+        assert_eq!(filesystem.size(FileSystem::ROOT), (100 << 40) + 1);
+        assert_eq!(assembly(input.lines().collect()), Ok(0));
+        assert_eq!(
+            free_space(
+                input.lines().collect(),
+                DiskLimits {
+                    capacity: 200 << 40,
+                    required_free: 150 << 40
+                }
+            ),
+            Ok(51 << 40)
+        );
+    }
 
-        println!("{result}");
+    #[test]
+    fn test_size_overflow() {
+        let maximum = format!("{} a", u64::MAX);
+        assert_eq!(
+            parse_input(vec!["$ cd /", "$ ls", &maximum, "$ cd /", "$ ls", "1 b"]),
+            Err(Error::parse(
+                7,
+                6,
+                1,
+                "1 b",
+                "the total size of the files does not fit in 64 bits"
+            ))
+        );
+    }
 
-        assert_eq!(result, expected)
+    fn sizes_by_path(filesystem: &FileSystem) -> HashMap<String, u64> {
+        filesystem
+            .sizes()
+            .into_iter()
+            .map(|(id, size)| (filesystem.path(id), size))
+            .collect()
     }
 
     #[test]
     fn test_map_creation() {
-        let input: Vec<&str> = "root/|14848514,8504156,
-root/a/|29116,2557,62596,
-root/a/e/|584,
-root/a/e/
-root/a/
-root/d/|4060174,8033020,5626152,7214296,"
-            .lines()
-            .collect();
+        let filesystem = example();
+        let expected: HashMap<String, u64> = HashMap::from([
+            ("/a".to_string(), 94853),
+            ("/a/e".to_string(), 584),
+            ("/d".to_string(), 24933642),
+            ("/".to_string(), 48381165),
+        ]);
+
+        assert_eq!(sizes_by_path(&filesystem), expected);
+    }
+
+    #[test]
+    fn test_duplicate_directory_names() {
+        let input = "$ cd /
+$ ls
+dir a
+dir b
+$ cd a
+$ ls
+dir d
+$ cd d
+$ ls
+1000 x
+$ cd ..
+$ cd ..
+$ cd b
+$ ls
+dir d
+$ cd d
+$ ls
+dir d
+2000 y
+$ cd d
+$ ls
+300000 z";
 
-        let expected: HashMap<String, u32> = HashMap::from([
-            ("a".to_string(), 94853),
-            ("e".to_string(), 584),
-            ("d".to_string(), 24933642),
-            ("root".to_string(), 48381165),
+        let filesystem = parse_input(input.lines().collect()).unwrap();
+        let expected: HashMap<String, u64> = HashMap::from([
+            ("/".to_string(), 303000),
+            ("/a".to_string(), 1000),
+            ("/a/d".to_string(), 1000),
+            ("/b".to_string(), 302000),
+            ("/b/d".to_string(), 302000),
+            ("/b/d/d".to_string(), 300000),
         ]);
 
-        assert_eq!(count_size(input), expected);
+        assert_eq!(sizes_by_path(&filesystem), expected);
+        assert_eq!(assembly(input.lines().collect()), Ok(2000));
     }
 }
@@ -0,0 +1,117 @@
+use super::filesystem::FileSystem;
+use super::query::{entries, Entry, Kind};
+use std::collections::BTreeMap;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Change {
+    Added,
+    Removed,
+    Resized,
+}
+
+/// A file or directory that differs between two snapshots. Directories compare their
+/// cumulative sizes, so every level above a change shows the net change below it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Difference {
+    pub path: String,
+    pub kind: Kind,
+    pub change: Change,
+    pub before: Option<u64>,
+    pub after: Option<u64>,
+}
+
+impl Difference {
+    /// Growth in bytes, negative when the entry shrank or was removed.
+    pub fn delta(&self) -> i128 {
+        i128::from(self.after.unwrap_or(0)) - i128::from(self.before.unwrap_or(0))
+    }
+}
+
+fn by_path(filesystem: &FileSystem) -> BTreeMap<(String, bool), Entry> {
+    entries(filesystem)
+        .into_iter()
+        .map(|entry| ((entry.path.clone(), entry.kind == Kind::File), entry))
+        .collect()
+}
+
+/// Differences between two snapshots, ordered by path.
+///
+/// A path that is a file in one snapshot and a directory in the other is reported as removed
+/// and added.
+pub fn diff(before: &FileSystem, after: &FileSystem) -> Vec<Difference> {
+    let before = by_path(before);
+    let mut after = by_path(after);
+    let mut differences: Vec<Difference> = Vec::new();
+    for (key, old) in before {
+        let new = after.remove(&key);
+        let change = match &new {
+            None => Change::Removed,
+            Some(new) if new.size != old.size => Change::Resized,
+            Some(_) => continue,
+        };
+        differences.push(Difference {
+            path: old.path,
+            kind: old.kind,
+            change,
+            before: Some(old.size),
+            after: new.map(|new| new.size),
+        });
+    }
+    differences.extend(after.into_values().map(|new| Difference {
+        path: new.path,
+        kind: new.kind,
+        change: Change::Added,
+        before: None,
+        after: Some(new.size),
+    }));
+    differences.sort_by(|left, right| left.path.cmp(&right.path));
+    differences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diff, Change, Difference};
+    use crate::challenges::day_07::filesystem::FileSystem;
+    use crate::challenges::day_07::query::Kind;
+
+    #[test]
+    fn test_diff() {
+        let mut before = FileSystem::new();
+        let a = before.add_directory(FileSystem::ROOT, "a").unwrap();
+        before.add_file(a, "f", 100).unwrap();
+        before.add_file(a, "g", 50).unwrap();
+        let old = before.add_directory(FileSystem::ROOT, "old").unwrap();
+        before.add_file(old, "x", 7).unwrap();
+        before.add_file(FileSystem::ROOT, "same", 1).unwrap();
+
+        let mut after = FileSystem::new();
+        let a = after.add_directory(FileSystem::ROOT, "a").unwrap();
+        after.add_file(a, "f", 300).unwrap();
+        after.add_file(a, "h", 20).unwrap();
+        after.add_file(FileSystem::ROOT, "same", 1).unwrap();
+
+        let difference = |path: &str, kind, change, before, after| Difference {
+            path: path.to_string(),
+            kind,
+            change,
+            before,
+            after,
+        };
+        let differences = diff(&before, &after);
+        assert_eq!(
+            differences,
+            vec![
+                difference("/", Kind::Directory, Change::Resized, Some(158), Some(321)),
+                difference("/a", Kind::Directory, Change::Resized, Some(150), Some(320)),
+                difference("/a/f", Kind::File, Change::Resized, Some(100), Some(300)),
+                difference("/a/g", Kind::File, Change::Removed, Some(50), None),
+                difference("/a/h", Kind::File, Change::Added, None, Some(20)),
+                difference("/old", Kind::Directory, Change::Removed, Some(7), None),
+                difference("/old/x", Kind::File, Change::Removed, Some(7), None),
+            ]
+        );
+        assert_eq!(differences[0].delta(), 163);
+        assert_eq!(differences[3].delta(), -50);
+        assert!(diff(&after, &after).is_empty());
+    }
+}
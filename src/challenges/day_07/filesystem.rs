@@ -0,0 +1,322 @@
+use crate::error::{Error, Result};
+use std::collections::BTreeMap;
+
+/// Why `name` cannot name a file or directory, with the offset of the problem in it, since
+/// every name has to be a single component of a path.
+pub fn invalid_name(name: &str) -> Option<(usize, &'static str)> {
+    if name.is_empty() {
+        return Some((0, "expected a name"));
+    }
+    if name == "." || name == ".." {
+        return Some((0, "`.` and `..` cannot name a file or directory"));
+    }
+    name.char_indices().find_map(|(offset, c)| match c {
+        '/' => Some((offset, "names cannot contain `/`")),
+        '\\' => Some((offset, "names cannot contain `\\`")),
+        '\0' => Some((offset, "names cannot contain a NUL character")),
+        _ => None,
+    })
+}
+
+/// Handle of a directory inside a [`FileSystem`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct DirectoryId(usize);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct File {
+    pub name: String,
+    pub size: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Directory {
+    name: String,
+    parent: Option<DirectoryId>,
+    subdirectories: BTreeMap<String, DirectoryId>,
+    files: Vec<File>,
+}
+
+impl Directory {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn parent(&self) -> Option<DirectoryId> {
+        self.parent
+    }
+
+    pub fn files(&self) -> &[File] {
+        &self.files
+    }
+
+    pub fn subdirectory(&self, name: &str) -> Option<DirectoryId> {
+        self.subdirectories.get(name).copied()
+    }
+
+    /// Subdirectories ordered by name.
+    pub fn subdirectories(&self) -> impl Iterator<Item = DirectoryId> + '_ {
+        self.subdirectories.values().copied()
+    }
+}
+
+/// Directory tree reconstructed from a terminal transcript.
+///
+/// Directories live in an arena and refer to each other by [`DirectoryId`], a directory is
+/// always created after its parent. Every name passes [`invalid_name`], so no two entries share
+/// a path. The sizes of all files add up to at most `u64::MAX`, so no directory size can
+/// overflow.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileSystem {
+    directories: Vec<Directory>,
+    total: u64,
+}
+
+impl Default for FileSystem {
+    fn default() -> Self {
+        FileSystem::new()
+    }
+}
+
+impl FileSystem {
+    pub const ROOT: DirectoryId = DirectoryId(0);
+
+    pub fn new() -> Self {
+        FileSystem {
+            directories: vec![Directory {
+                name: String::from("/"),
+                parent: None,
+                subdirectories: BTreeMap::new(),
+                files: Vec::new(),
+            }],
+            total: 0,
+        }
+    }
+
+    pub fn directory(&self, id: DirectoryId) -> &Directory {
+        &self.directories[id.0]
+    }
+
+    fn check_name(&self, directory: DirectoryId, name: &str) -> Result<()> {
+        match invalid_name(name) {
+            Some((_, message)) => Err(Error::invalid(
+                7,
+                &format!(
+                    "invalid name `{name}` in {}: {message}",
+                    self.path(directory)
+                ),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the subdirectory with the given name, creating it if it does not exist yet.
+    pub fn add_directory(&mut self, parent: DirectoryId, name: &str) -> Result<DirectoryId> {
+        self.check_name(parent, name)?;
+        if let Some(existing) = self.directory(parent).subdirectories.get(name) {
+            return Ok(*existing);
+        }
+        let id = DirectoryId(self.directories.len());
+        self.directories.push(Directory {
+            name: name.to_string(),
+            parent: Some(parent),
+            subdirectories: BTreeMap::new(),
+            files: Vec::new(),
+        });
+        self.directories[parent.0]
+            .subdirectories
+            .insert(name.to_string(), id);
+        Ok(id)
+    }
+
+    /// Adds a file to the directory, replacing a file with the same name.
+    ///
+    /// Fails without changing anything if the name is invalid or the sizes of all files would no
+    /// longer fit in 64 bits.
+    pub fn add_file(&mut self, directory: DirectoryId, name: &str, size: u64) -> Result<()> {
+        self.check_name(directory, name)?;
+        let files = &mut self.directories[directory.0].files;
+        let existing = files.iter_mut().find(|existing| existing.name == name);
+        let replaced = existing.as_ref().map_or(0, |existing| existing.size);
+        self.total = (self.total - replaced).checked_add(size).ok_or_else(|| {
+            Error::invalid(7, "the total size of the files does not fit in 64 bits")
+        })?;
+        let file = File {
+            name: name.to_string(),
+            size,
+        };
+        match existing {
+            Some(existing) => *existing = file,
+            None => files.push(file),
+        }
+        Ok(())
+    }
+
+    /// Absolute path of the directory, like `/a/e`.
+    pub fn path(&self, id: DirectoryId) -> String {
+        let mut names: Vec<&str> = Vec::new();
+        let mut current = id;
+        while let Some(parent) = self.directory(current).parent {
+            names.push(self.directory(current).name());
+            current = parent;
+        }
+        names.reverse();
+        format!("/{}", names.join("/"))
+    }
+
+    /// Absolute path of the entry called `name` in the directory, like `/a/e/i`.
+    pub fn child_path(&self, directory: DirectoryId, name: &str) -> String {
+        let path = self.path(directory);
+        format!("{}/{name}", path.trim_end_matches('/'))
+    }
+
+    /// Finds a directory by its absolute path.
+    pub fn lookup(&self, path: &str) -> Option<DirectoryId> {
+        path.split('/')
+            .filter(|name| !name.is_empty())
+            .try_fold(FileSystem::ROOT, |current, name| {
+                self.directory(current).subdirectory(name)
+            })
+    }
+
+    /// All directories in depth-first order, starting with the root.
+    pub fn walk(&self) -> Vec<DirectoryId> {
+        let mut order = Vec::with_capacity(self.directories.len());
+        let mut stack = vec![FileSystem::ROOT];
+        while let Some(id) = stack.pop() {
+            order.push(id);
+            let subdirectories: Vec<DirectoryId> = self.directory(id).subdirectories().collect();
+            stack.extend(subdirectories.into_iter().rev());
+        }
+        order
+    }
+
+    /// Total size of the files in the directory and all of its subdirectories.
+    ///
+    /// Walks the tree with an explicit stack, transcripts can nest directories far deeper than
+    /// the call stack allows.
+    pub fn size(&self, id: DirectoryId) -> u64 {
+        if id == FileSystem::ROOT {
+            return self.total;
+        }
+        let mut size = 0;
+        let mut stack = vec![id];
+        while let Some(current) = stack.pop() {
+            let directory = self.directory(current);
+            size += directory.files.iter().map(|file| file.size).sum::<u64>();
+            stack.extend(directory.subdirectories());
+        }
+        size
+    }
+
+    /// Total sizes of all directories.
+    pub fn sizes(&self) -> BTreeMap<DirectoryId, u64> {
+        let mut sizes: Vec<u64> = self
+            .directories
+            .iter()
+            .map(|directory| directory.files.iter().map(|file| file.size).sum())
+            .collect();
+        for (index, directory) in self.directories.iter().enumerate().rev() {
+            if let Some(parent) = directory.parent {
+                sizes[parent.0] += sizes[index];
+            }
+        }
+        sizes
+            .into_iter()
+            .enumerate()
+            .map(|(index, size)| (DirectoryId(index), size))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{File, FileSystem};
+    use crate::challenges::day_07::{deep_tree, example};
+    use crate::error::Error;
+
+    #[test]
+    fn test_paths_and_lookup() {
+        let filesystem = example();
+        let e = filesystem.lookup("/a/e").unwrap();
+
+        assert_eq!(filesystem.path(e), "/a/e");
+        assert_eq!(filesystem.path(FileSystem::ROOT), "/");
+        assert_eq!(filesystem.child_path(e, "i"), "/a/e/i");
+        assert_eq!(filesystem.child_path(FileSystem::ROOT, "b.txt"), "/b.txt");
+        assert_eq!(filesystem.lookup("/"), Some(FileSystem::ROOT));
+        assert_eq!(filesystem.lookup("/a/x"), None);
+        assert_eq!(
+            filesystem.directory(e).files(),
+            &[File {
+                name: "i".to_string(),
+                size: 584
+            }]
+        );
+    }
+
+    #[test]
+    fn test_add_existing_directory() {
+        let mut filesystem = example();
+        let a = filesystem.lookup("/a").unwrap();
+        assert_eq!(filesystem.add_directory(FileSystem::ROOT, "a"), Ok(a));
+    }
+
+    #[test]
+    fn test_invalid_names() {
+        let mut filesystem = example();
+        let a = filesystem.lookup("/a").unwrap();
+        assert_eq!(
+            filesystem.add_directory(a, "b/c"),
+            Err(Error::invalid(
+                7,
+                "invalid name `b/c` in /a: names cannot contain `/`"
+            ))
+        );
+        assert_eq!(
+            filesystem.add_directory(FileSystem::ROOT, ".."),
+            Err(Error::invalid(
+                7,
+                "invalid name `..` in /: `.` and `..` cannot name a file or directory"
+            ))
+        );
+        assert_eq!(
+            filesystem.add_file(a, "", 1),
+            Err(Error::invalid(7, "invalid name `` in /a: expected a name"))
+        );
+        assert_eq!(
+            filesystem.add_file(a, "x\\y", 1),
+            Err(Error::invalid(
+                7,
+                "invalid name `x\\y` in /a: names cannot contain `\\`"
+            ))
+        );
+        assert_eq!(filesystem, example());
+    }
+
+    #[test]
+    fn test_walk() {
+        let filesystem = example();
+        let paths: Vec<String> = filesystem
+            .walk()
+            .into_iter()
+            .map(|id| filesystem.path(id))
+            .collect();
+        assert_eq!(paths, vec!["/", "/a", "/a/e", "/d"]);
+    }
+
+    #[test]
+    fn test_sizes() {
+        let filesystem = example();
+        assert_eq!(filesystem.size(filesystem.lookup("/a").unwrap()), 94853);
+        assert_eq!(filesystem.size(FileSystem::ROOT), 48381165);
+    }
+
+    #[test]
+    fn test_size_of_deep_tree() {
+        let filesystem = deep_tree(20000);
+        let top = filesystem.lookup("/d").unwrap();
+
+        assert_eq!(filesystem.size(top), 7);
+        assert_eq!(filesystem.size(FileSystem::ROOT), 7);
+    }
+}
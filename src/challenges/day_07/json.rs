@@ -0,0 +1,270 @@
+use super::filesystem::{DirectoryId, FileSystem};
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JsonFile {
+    name: String,
+    size: u64,
+}
+
+/// A directory with its contents. `size` is the cumulative size, it is always written and may
+/// be left out when importing.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JsonDirectory {
+    name: String,
+    #[serde(default)]
+    size: Option<u64>,
+    #[serde(default)]
+    directories: Vec<JsonDirectory>,
+    #[serde(default)]
+    files: Vec<JsonFile>,
+}
+
+/// Takes deeply nested directories apart one level at a time instead of recursing.
+impl Drop for JsonDirectory {
+    fn drop(&mut self) {
+        let mut stack = std::mem::take(&mut self.directories);
+        while let Some(mut directory) = stack.pop() {
+            stack.append(&mut directory.directories);
+        }
+    }
+}
+
+fn to_json(filesystem: &FileSystem) -> JsonDirectory {
+    let sizes = filesystem.sizes();
+    let mut built: BTreeMap<DirectoryId, JsonDirectory> = BTreeMap::new();
+    // Going through the walk backwards builds every directory after all of its subdirectories.
+    for id in filesystem.walk().into_iter().rev() {
+        let directory = filesystem.directory(id);
+        let json = JsonDirectory {
+            name: directory.name().to_string(),
+            size: Some(sizes[&id]),
+            directories: directory
+                .subdirectories()
+                .map(|subdirectory| {
+                    built
+                        .remove(&subdirectory)
+                        .expect("subdirectories are built first")
+                })
+                .collect(),
+            files: directory
+                .files()
+                .iter()
+                .map(|file| JsonFile {
+                    name: file.name.clone(),
+                    size: file.size,
+                })
+                .collect(),
+        };
+        built.insert(id, json);
+    }
+    built
+        .remove(&FileSystem::ROOT)
+        .expect("the root is built last")
+}
+
+/// Writes the file system as nested JSON objects with names, sizes and cumulative totals.
+///
+/// ```json
+/// {"name": "/", "size": 584, "directories": [], "files": [{"name": "i", "size": 584}]}
+/// ```
+pub fn export(filesystem: &FileSystem) -> String {
+    let root = to_json(filesystem);
+    let mut json = Vec::new();
+    let mut serializer = serde_json::Serializer::pretty(&mut json);
+    // The nesting follows the directories, so it grows the stack instead of overflowing it.
+    root.serialize(serde_stacker::Serializer::new(&mut serializer))
+        .expect("a file system only holds strings and numbers");
+    String::from_utf8(json).expect("serde_json writes UTF-8")
+}
+
+fn duplicate(filesystem: &FileSystem, id: DirectoryId, name: &str) -> Error {
+    let path = filesystem.child_path(id, name);
+    Error::invalid(7, &format!("{path} appears twice"))
+}
+
+/// Adds the contents of a directory, returning its subdirectories to add next. Names are checked
+/// by the file system itself.
+fn add_contents<'a>(
+    filesystem: &mut FileSystem,
+    id: DirectoryId,
+    directory: &'a JsonDirectory,
+) -> Result<Vec<(DirectoryId, &'a JsonDirectory)>> {
+    for file in directory.files.iter() {
+        if filesystem
+            .directory(id)
+            .files()
+            .iter()
+            .any(|existing| existing.name == file.name)
+        {
+            return Err(duplicate(filesystem, id, &file.name));
+        }
+        filesystem.add_file(id, &file.name, file.size)?;
+    }
+    let mut subdirectories = Vec::with_capacity(directory.directories.len());
+    for subdirectory in directory.directories.iter() {
+        let contents = filesystem.directory(id);
+        if contents.subdirectory(&subdirectory.name).is_some()
+            || contents
+                .files()
+                .iter()
+                .any(|file| file.name == subdirectory.name)
+        {
+            return Err(duplicate(filesystem, id, &subdirectory.name));
+        }
+        let child = filesystem.add_directory(id, &subdirectory.name)?;
+        subdirectories.push((child, subdirectory));
+    }
+    Ok(subdirectories)
+}
+
+/// Reads a file system written by [`export`], checking any cumulative sizes it gives.
+pub fn import(json: &str) -> Result<FileSystem> {
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    deserializer.disable_recursion_limit();
+    let root = JsonDirectory::deserialize(serde_stacker::Deserializer::new(&mut deserializer))
+        .and_then(|root| deserializer.end().map(|()| root))
+        .map_err(|err| {
+            let text = json.lines().nth(err.line().saturating_sub(1)).unwrap_or("");
+            let position = format!(" at line {} column {}", err.line(), err.column());
+            let message = err.to_string();
+            let message = message.strip_suffix(&position).unwrap_or(&message);
+            Error::parse(7, err.line(), err.column(), text, message)
+        })?;
+    if root.name != "/" {
+        return Err(Error::invalid(
+            7,
+            &format!("the root is called `{}` instead of `/`", root.name),
+        ));
+    }
+
+    let mut filesystem = FileSystem::new();
+    let mut declared: Vec<(DirectoryId, u64)> = Vec::new();
+    let mut pending = vec![(FileSystem::ROOT, &root)];
+    while let Some((id, directory)) = pending.pop() {
+        declared.extend(directory.size.map(|size| (id, size)));
+        pending.extend(
+            add_contents(&mut filesystem, id, directory)?
+                .into_iter()
+                .rev(),
+        );
+    }
+
+    let sizes = filesystem.sizes();
+    for (id, size) in declared {
+        if size != sizes[&id] {
+            return Err(Error::invalid(
+                7,
+                &format!(
+                    "{} has size {size}, but its contents add up to {}",
+                    filesystem.path(id),
+                    sizes[&id]
+                ),
+            ));
+        }
+    }
+    Ok(filesystem)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{export, import};
+    use crate::challenges::day_07::filesystem::FileSystem;
+    use crate::challenges::day_07::{deep_tree, example};
+    use crate::error::Error;
+
+    #[test]
+    fn test_round_trip() {
+        let mut filesystem = example();
+        filesystem.add_directory(FileSystem::ROOT, "empty").unwrap();
+
+        let json = export(&filesystem);
+        assert!(json.contains(r#""size": 48381165"#));
+        // The arena of the import is filled in a different order, so compare the contents.
+        assert_eq!(import(&json).map(|imported| export(&imported)), Ok(json));
+    }
+
+    #[test]
+    fn test_import_without_totals() {
+        let filesystem = import(
+            r#"{"name": "/", "directories": [{"name": "a", "files": [{"name": "f", "size": 7}]}]}"#,
+        )
+        .unwrap();
+        assert_eq!(filesystem.size(FileSystem::ROOT), 7);
+        assert_eq!(filesystem.lookup("/a").map(|a| filesystem.size(a)), Some(7));
+    }
+
+    #[test]
+    fn test_import_errors() {
+        assert_eq!(
+            import(r#"{"name": "/", "size": 8, "files": [{"name": "f", "size": 7}]}"#),
+            Err(Error::invalid(
+                7,
+                "/ has size 8, but its contents add up to 7"
+            ))
+        );
+        assert_eq!(
+            import(
+                r#"{"name": "/", "files": [{"name": "f", "size": 7}, {"name": "f", "size": 1}]}"#
+            ),
+            Err(Error::invalid(7, "/f appears twice"))
+        );
+        assert!(matches!(
+            import("{\n  \"name\": \"/\",\n  \"files\": 3\n}"),
+            Err(Error::Parse { line: 3, .. })
+        ));
+        assert!(matches!(
+            import(r#"{"name": "/"} {"name": "/"}"#),
+            Err(Error::Parse { column: 15, .. })
+        ));
+    }
+
+    #[test]
+    fn test_import_invalid_names() {
+        assert_eq!(
+            import(r#"{"name": "root"}"#),
+            Err(Error::invalid(
+                7,
+                "the root is called `root` instead of `/`"
+            ))
+        );
+        assert_eq!(
+            import(r#"{"name": "/", "directories": [{"name": "a/b"}]}"#),
+            Err(Error::invalid(
+                7,
+                "invalid name `a/b` in /: names cannot contain `/`"
+            ))
+        );
+        assert_eq!(
+            import(
+                r#"{"name": "/", "directories": [{"name": "a", "files": [{"name": "..", "size": 1}]}]}"#
+            ),
+            Err(Error::invalid(
+                7,
+                "invalid name `..` in /a: `.` and `..` cannot name a file or directory"
+            ))
+        );
+        assert_eq!(
+            import(r#"{"name": "/", "directories": [{"name": ""}]}"#),
+            Err(Error::invalid(7, "invalid name `` in /: expected a name"))
+        );
+        assert_eq!(
+            import(
+                r#"{"name": "/", "files": [{"name": "x", "size": 1}], "directories": [{"name": "x"}]}"#
+            ),
+            Err(Error::invalid(7, "/x appears twice"))
+        );
+    }
+
+    #[test]
+    fn test_round_trip_of_deep_tree() {
+        // Pretty printing indents every level, so the JSON grows with the square of the depth.
+        let filesystem = deep_tree(1000);
+        let json = export(&filesystem);
+        assert_eq!(import(&json), Ok(filesystem));
+    }
+}
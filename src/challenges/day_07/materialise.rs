@@ -0,0 +1,122 @@
+use super::filesystem::{DirectoryId, FileSystem};
+use crate::error::{Error, Result};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::path::{Path, PathBuf};
+
+fn io_error(path: &Path, err: std::io::Error) -> Error {
+    Error::Io {
+        message: format!("could not write {}: {err}", path.display()),
+    }
+}
+
+/// Joins a name from the file system to a path. The file system only holds names of a single
+/// path component, so the path stays inside `parent`.
+fn child(parent: &Path, name: &str) -> Result<PathBuf> {
+    let path = parent.join(name);
+    // An existing symbolic link could point anywhere, so never write through one.
+    if path
+        .symlink_metadata()
+        .is_ok_and(|metadata| metadata.is_symlink())
+    {
+        return Err(Error::invalid(
+            7,
+            &format!("refusing to write through the link {}", path.display()),
+        ));
+    }
+    Ok(path)
+}
+
+/// Recreates the file system below `target`, with sparse files of the listed sizes.
+///
+/// The target directory is created if needed. Nothing is written until every path has been
+/// checked, so a conflicting file system leaves the disk untouched.
+pub fn materialise(filesystem: &FileSystem, target: &Path) -> Result<()> {
+    let mut paths: HashMap<DirectoryId, PathBuf> = HashMap::new();
+    let mut directories: Vec<PathBuf> = Vec::new();
+    let mut files: Vec<(PathBuf, u64)> = Vec::new();
+    for id in filesystem.walk() {
+        let directory = filesystem.directory(id);
+        let path = match directory.parent() {
+            Some(parent) => child(&paths[&parent], directory.name())?,
+            None => target.to_path_buf(),
+        };
+        for file in directory.files() {
+            let file_path = child(&path, &file.name)?;
+            if directory.subdirectory(&file.name).is_some() {
+                return Err(Error::invalid(
+                    7,
+                    &format!("{} is both a file and a directory", file_path.display()),
+                ));
+            }
+            files.push((file_path, file.size));
+        }
+        directories.push(path.clone());
+        paths.insert(id, path);
+    }
+
+    for directory in directories.iter() {
+        fs::create_dir_all(directory).map_err(|err| io_error(directory, err))?;
+    }
+    for (path, size) in files.iter() {
+        OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)
+            .and_then(|file| file.set_len(*size))
+            .map_err(|err| io_error(path, err))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::materialise;
+    use crate::challenges::day_07::example;
+    use crate::challenges::day_07::filesystem::FileSystem;
+    use crate::error::Error;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn scratch_directory(name: &str) -> PathBuf {
+        let directory =
+            std::env::temp_dir().join(format!("aoc_2022_{name}_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        directory
+    }
+
+    #[test]
+    fn test_materialise() {
+        let mut filesystem = example();
+        filesystem.add_directory(FileSystem::ROOT, "empty").unwrap();
+        let target = scratch_directory("materialise");
+
+        materialise(&filesystem, &target).unwrap();
+
+        assert_eq!(fs::metadata(target.join("b.txt")).unwrap().len(), 14848514);
+        assert_eq!(fs::metadata(target.join("a/e/i")).unwrap().len(), 584);
+        assert!(target.join("empty").is_dir());
+        fs::remove_dir_all(&target).unwrap();
+    }
+
+    #[test]
+    fn test_refuse_file_and_directory_with_the_same_name() {
+        let mut filesystem = FileSystem::new();
+        filesystem.add_directory(FileSystem::ROOT, "x").unwrap();
+        filesystem.add_file(FileSystem::ROOT, "x", 5).unwrap();
+        let target = scratch_directory("conflict");
+
+        assert_eq!(
+            materialise(&filesystem, &target),
+            Err(Error::invalid(
+                7,
+                &format!(
+                    "{} is both a file and a directory",
+                    target.join("x").display()
+                )
+            ))
+        );
+        assert!(!target.exists());
+    }
+}
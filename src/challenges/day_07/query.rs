@@ -0,0 +1,480 @@
+use super::filesystem::FileSystem;
+use crate::error::{Error, Result};
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    File,
+    Directory,
+}
+
+/// A file or directory of the file system, as seen by a query.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub path: String,
+    pub kind: Kind,
+    /// Size of the file, or cumulative size of the directory.
+    pub size: u64,
+    /// Number of directories above the entry, the root has depth 0.
+    pub depth: usize,
+}
+
+impl Entry {
+    pub fn name(&self) -> &str {
+        match self.path.rsplit_once('/') {
+            Some((_, "")) | None => &self.path,
+            Some((_, name)) => name,
+        }
+    }
+
+    /// Part of the name after the last dot, `None` for names like `.profile`.
+    pub fn extension(&self) -> Option<&str> {
+        match self.name().rsplit_once('.') {
+            Some(("", _)) | None => None,
+            Some((_, extension)) => Some(extension),
+        }
+    }
+}
+
+/// Every file and directory, ordered by path.
+pub fn entries(filesystem: &FileSystem) -> Vec<Entry> {
+    let sizes = filesystem.sizes();
+    let mut entries: Vec<Entry> = Vec::new();
+    for id in filesystem.walk() {
+        let path = filesystem.path(id);
+        let depth = path.split('/').filter(|name| !name.is_empty()).count();
+        entries.extend(filesystem.directory(id).files().iter().map(|file| Entry {
+            path: filesystem.child_path(id, &file.name),
+            kind: Kind::File,
+            size: file.size,
+            depth: depth + 1,
+        }));
+        entries.push(Entry {
+            path,
+            kind: Kind::Directory,
+            size: sizes[&id],
+            depth,
+        });
+    }
+    entries.sort_by(|left, right| left.path.cmp(&right.path));
+    entries
+}
+
+/// A field of an entry together with the value it is compared to, so every field is always
+/// compared to a value of its own type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Field {
+    Size(u64),
+    Depth(u64),
+    Name(String),
+    Extension(String),
+    Type(Kind),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operator {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+    NotEqual,
+}
+
+/// Filter expression like `size > 1M and depth <= 2`.
+///
+/// Comparisons on `size`, `depth`, `name`, `ext` and `type` are combined with `and`, `or`,
+/// `not` and parentheses. Sizes take the binary suffixes `K`, `M`, `G` and `T`, names may be
+/// quoted with `'` or `"`, and `type` is either `file` or `dir`.
+///
+/// `and` and `or` hold all filters of a chain like `a and b and c` side by side, so only `not`
+/// and parentheses nest, at most [`MAX_NESTING`] levels deep.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Filter {
+    And(Vec<Filter>),
+    Or(Vec<Filter>),
+    Not(Box<Filter>),
+    Compare(Field, Operator),
+}
+
+impl Operator {
+    fn holds<T: Ord + ?Sized>(self, left: &T, right: &T) -> bool {
+        match self {
+            Operator::Less => left < right,
+            Operator::LessOrEqual => left <= right,
+            Operator::Greater => left > right,
+            Operator::GreaterOrEqual => left >= right,
+            Operator::Equal => left == right,
+            Operator::NotEqual => left != right,
+        }
+    }
+}
+
+impl Filter {
+    pub fn matches(&self, entry: &Entry) -> bool {
+        match self {
+            Filter::And(filters) => filters.iter().all(|filter| filter.matches(entry)),
+            Filter::Or(filters) => filters.iter().any(|filter| filter.matches(entry)),
+            Filter::Not(filter) => !filter.matches(entry),
+            Filter::Compare(field, operator) => match field {
+                Field::Size(size) => operator.holds(&entry.size, size),
+                Field::Depth(depth) => operator.holds(&(entry.depth as u64), depth),
+                Field::Name(name) => operator.holds(entry.name(), name.as_str()),
+                Field::Extension(extension) => {
+                    operator.holds(entry.extension().unwrap_or(""), extension.as_str())
+                }
+                Field::Type(kind) => operator.holds(&(entry.kind == *kind), &true),
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Operator(Operator),
+    Open,
+    Close,
+}
+
+/// Splits the expression into tokens, each with its one based column.
+fn tokenize(expression: &str) -> Result<Vec<(usize, Token)>> {
+    let error = |index: usize, message: &str| Error::filter(index + 1, expression, message);
+    let mut tokens: Vec<(usize, Token)> = Vec::new();
+    let mut chars = expression.char_indices().peekable();
+    while let Some((start, char)) = chars.next() {
+        let token = match char {
+            ' ' | '\t' => continue,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '<' | '>' | '=' | '!' => {
+                let equals = chars.next_if(|(_, next)| *next == '=').is_some();
+                Token::Operator(match (char, equals) {
+                    ('<', false) => Operator::Less,
+                    ('<', true) => Operator::LessOrEqual,
+                    ('>', false) => Operator::Greater,
+                    ('>', true) => Operator::GreaterOrEqual,
+                    ('=', _) => Operator::Equal,
+                    ('!', true) => Operator::NotEqual,
+                    _ => return Err(error(start, "expected `!=`")),
+                })
+            }
+            '\'' | '"' => {
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, next)) if next == char => break,
+                        Some((_, next)) => text.push(next),
+                        None => return Err(error(start, "unterminated quote")),
+                    }
+                }
+                Token::Quoted(text)
+            }
+            _ => {
+                let mut word = String::from(char);
+                while let Some((_, next)) =
+                    chars.next_if(|(_, next)| !" \t()<>=!'\"".contains(*next))
+                {
+                    word.push(next);
+                }
+                Token::Word(word)
+            }
+        };
+        tokens.push((start + 1, token));
+    }
+    Ok(tokens)
+}
+
+/// Parses sizes like `100000`, `64K` or `1.5M`.
+fn parse_size(text: &str) -> Option<u64> {
+    let (number, multiplier) = match text.char_indices().last()? {
+        (index, 'K' | 'k') => (&text[..index], 1u64 << 10),
+        (index, 'M' | 'm') => (&text[..index], 1 << 20),
+        (index, 'G' | 'g') => (&text[..index], 1 << 30),
+        (index, 'T' | 't') => (&text[..index], 1 << 40),
+        _ => (text, 1),
+    };
+    if let Ok(number) = number.parse::<u64>() {
+        return number.checked_mul(multiplier);
+    }
+    let number: f64 = number.parse().ok()?;
+    (number.is_finite() && number >= 0.0).then_some((number * multiplier as f64) as u64)
+}
+
+/// Names of the fields in filter expressions.
+const FIELDS: [&str; 5] = ["size", "depth", "name", "ext", "type"];
+
+/// How deep `not` and parentheses may nest, the parser recurses once per level.
+pub const MAX_NESTING: usize = 100;
+
+struct Parser<'a> {
+    expression: &'a str,
+    tokens: Vec<(usize, Token)>,
+    position: usize,
+    /// Number of `not` and parentheses around the current position.
+    nesting: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> Error {
+        let column = match self.tokens.get(self.position) {
+            Some((column, _)) => *column,
+            None => self.expression.len() + 1,
+        };
+        Error::filter(column, self.expression, message)
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(_, token)| token)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.peek().cloned();
+        self.position += 1;
+        token
+    }
+
+    fn keyword(&mut self, keyword: &str) -> bool {
+        let found = matches!(self.peek(), Some(Token::Word(word)) if word == keyword);
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn expression(&mut self) -> Result<Filter> {
+        let mut filters = vec![self.term()?];
+        while self.keyword("or") {
+            filters.push(self.term()?);
+        }
+        Ok(match filters.len() {
+            1 => filters.remove(0),
+            _ => Filter::Or(filters),
+        })
+    }
+
+    fn term(&mut self) -> Result<Filter> {
+        let mut filters = vec![self.factor()?];
+        while self.keyword("and") {
+            filters.push(self.factor()?);
+        }
+        Ok(match filters.len() {
+            1 => filters.remove(0),
+            _ => Filter::And(filters),
+        })
+    }
+
+    fn factor(&mut self) -> Result<Filter> {
+        let not = matches!(self.peek(), Some(Token::Word(word)) if word == "not");
+        if !not && self.peek() != Some(&Token::Open) {
+            return self.comparison();
+        }
+        if self.nesting == MAX_NESTING {
+            return Err(self.error(&format!(
+                "filters cannot nest more than {MAX_NESTING} levels deep"
+            )));
+        }
+        self.position += 1;
+        self.nesting += 1;
+        let filter = if not {
+            Filter::Not(Box::new(self.factor()?))
+        } else {
+            let filter = self.expression()?;
+            if self.peek() != Some(&Token::Close) {
+                return Err(self.error("expected `)`"));
+            }
+            self.position += 1;
+            filter
+        };
+        self.nesting -= 1;
+        Ok(filter)
+    }
+
+    fn comparison(&mut self) -> Result<Filter> {
+        let name = match self.peek() {
+            Some(Token::Word(word)) if FIELDS.contains(&word.as_str()) => word.clone(),
+            Some(Token::Word(_)) => {
+                return Err(self.error("expected one of size, depth, name, ext or type"))
+            }
+            _ => return Err(self.error("expected a comparison like `size > 1M`")),
+        };
+        self.position += 1;
+        let operator = match self.peek() {
+            Some(Token::Operator(operator)) => *operator,
+            _ => return Err(self.error("expected one of <, <=, >, >=, = or !=")),
+        };
+        self.position += 1;
+        let text = match self.peek() {
+            Some(Token::Word(text) | Token::Quoted(text)) => text.clone(),
+            _ => return Err(self.error("expected a value")),
+        };
+        let field = match name.as_str() {
+            "size" => parse_size(&text).map(Field::Size),
+            "depth" => text.parse().ok().map(Field::Depth),
+            "name" => Some(Field::Name(text)),
+            "ext" => Some(Field::Extension(text)),
+            // `type` is the only field left.
+            _ => match text.as_str() {
+                "file" => Some(Field::Type(Kind::File)),
+                "dir" => Some(Field::Type(Kind::Directory)),
+                _ => return Err(self.error("expected `file` or `dir`")),
+            },
+        }
+        .ok_or_else(|| self.error("expected a number"))?;
+        if matches!(field, Field::Type(_))
+            && !matches!(operator, Operator::Equal | Operator::NotEqual)
+        {
+            return Err(self.error("type can only be compared with = or !="));
+        }
+        self.next();
+        Ok(Filter::Compare(field, operator))
+    }
+}
+
+impl FromStr for Filter {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut parser = Parser {
+            expression: s,
+            tokens: tokenize(s)?,
+            position: 0,
+            nesting: 0,
+        };
+        let filter = parser.expression()?;
+        if parser.peek().is_some() {
+            return Err(parser.error("expected `and` or `or`"));
+        }
+        Ok(filter)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Query {
+    pub filter: Option<Filter>,
+    /// Only keep this many entries, biggest first.
+    pub largest: Option<usize>,
+}
+
+/// Entries matching the query, ordered by path or by size when only the largest are kept.
+pub fn run(filesystem: &FileSystem, query: &Query) -> Vec<Entry> {
+    let mut entries: Vec<Entry> = entries(filesystem)
+        .into_iter()
+        .filter(|entry| match &query.filter {
+            Some(filter) => filter.matches(entry),
+            None => true,
+        })
+        .collect();
+    if let Some(largest) = query.largest {
+        entries.sort_by(|left, right| right.size.cmp(&left.size).then(left.path.cmp(&right.path)));
+        entries.truncate(largest);
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_size, run, Field, Filter, Kind, Operator, Query, MAX_NESTING};
+    use crate::challenges::day_07::example;
+    use crate::error::Error;
+
+    fn paths(filter: &str, largest: Option<usize>) -> Vec<String> {
+        let query = Query {
+            filter: Some(filter.parse().unwrap()),
+            largest,
+        };
+        run(&example(), &query)
+            .into_iter()
+            .map(|entry| entry.path)
+            .collect()
+    }
+
+    #[test]
+    fn test_filters() {
+        assert_eq!(
+            paths("size > 1M and depth <= 1", None),
+            vec!["/", "/b.txt", "/c.dat", "/d"]
+        );
+        assert_eq!(
+            paths("type = dir and size <= 100000", None),
+            vec!["/a", "/a/e"]
+        );
+        assert_eq!(
+            paths("ext = lst or ext = 'log'", None),
+            vec!["/a/h.lst", "/d/d.log"]
+        );
+        assert_eq!(
+            paths("not (type = dir or depth < 2) and name != k", None),
+            vec!["/a/e/i", "/a/f", "/a/g", "/a/h.lst", "/d/d.ext", "/d/d.log", "/d/j"]
+        );
+        assert_eq!(paths("type = file", Some(2)), vec!["/b.txt", "/c.dat"]);
+    }
+
+    #[test]
+    fn test_parse_comparisons() {
+        assert_eq!(
+            "size >= 64K".parse::<Filter>(),
+            Ok(Filter::Compare(
+                Field::Size(65536),
+                Operator::GreaterOrEqual
+            ))
+        );
+        assert_eq!(
+            "type != dir".parse::<Filter>(),
+            Ok(Filter::Compare(
+                Field::Type(Kind::Directory),
+                Operator::NotEqual
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            "size > lots".parse::<Filter>(),
+            Err(Error::filter(8, "size > lots", "expected a number"))
+        );
+        assert_eq!(
+            "size > 1M depth".parse::<Filter>(),
+            Err(Error::filter(
+                11,
+                "size > 1M depth",
+                "expected `and` or `or`"
+            ))
+        );
+        assert!("colour = red".parse::<Filter>().is_err());
+        assert!("type > dir".parse::<Filter>().is_err());
+        assert!("(size > 1".parse::<Filter>().is_err());
+        assert!("name = 'a".parse::<Filter>().is_err());
+    }
+
+    #[test]
+    fn test_nesting() {
+        let nested = |depth: usize| format!("{}size > 1{}", "(".repeat(depth), ")".repeat(depth));
+        assert!(nested(MAX_NESTING).parse::<Filter>().is_ok());
+        let too_deep = nested(50000);
+        assert_eq!(
+            too_deep.parse::<Filter>(),
+            Err(Error::filter(
+                MAX_NESTING + 1,
+                &too_deep,
+                "filters cannot nest more than 100 levels deep"
+            ))
+        );
+        assert!(format!("{}type = dir", "not ".repeat(50000))
+            .parse::<Filter>()
+            .is_err());
+
+        let chain = vec!["size > 1"; 50000].join(" and ");
+        assert_eq!(paths(&chain, None).len(), 14);
+    }
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("100000"), Some(100000));
+        assert_eq!(parse_size("64K"), Some(65536));
+        assert_eq!(parse_size("1.5M"), Some(1572864));
+        assert_eq!(parse_size("1x"), None);
+    }
+}
@@ -0,0 +1,170 @@
+use super::filesystem::{DirectoryId, FileSystem};
+use std::collections::BTreeMap;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RenderOptions {
+    /// Print sizes like `46.1M` instead of a plain number of bytes.
+    pub human_readable: bool,
+    /// List the biggest entries of a directory first instead of ordering them by name.
+    pub sort_by_size: bool,
+}
+
+enum Entry<'a> {
+    Directory(DirectoryId),
+    File(&'a str, u64),
+}
+
+/// Formats a size with binary units, e.g. `584B`, `28.4K` or `46.1M`.
+pub fn human_size(size: u64) -> String {
+    const UNITS: [&str; 6] = ["K", "M", "G", "T", "P", "E"];
+    if size < 1024 {
+        return format!("{size}B");
+    }
+    let mut value = size as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1}{}", UNITS[unit])
+}
+
+/// Renders the file system as an indented tree in the style of the puzzle description,
+/// with the cumulative size of every directory.
+///
+/// ```text
+/// - / (dir, 48381165)
+///   - a (dir, 94853)
+///     - e (dir, 584)
+///       - i (file, 584)
+/// ```
+pub fn tree(filesystem: &FileSystem, options: RenderOptions) -> String {
+    let sizes = filesystem.sizes();
+    let format_size = |size: u64| {
+        if options.human_readable {
+            human_size(size)
+        } else {
+            size.to_string()
+        }
+    };
+
+    let mut lines: Vec<String> = Vec::new();
+    let mut stack: Vec<(usize, Entry)> = vec![(0, Entry::Directory(FileSystem::ROOT))];
+    while let Some((depth, entry)) = stack.pop() {
+        let indent = "  ".repeat(depth);
+        match entry {
+            Entry::File(name, size) => {
+                lines.push(format!("{indent}- {name} (file, {})", format_size(size)));
+            }
+            Entry::Directory(id) => {
+                let directory = filesystem.directory(id);
+                lines.push(format!(
+                    "{indent}- {} (dir, {})",
+                    directory.name(),
+                    format_size(sizes[&id])
+                ));
+                let mut children = children(filesystem, id, &sizes);
+                if options.sort_by_size {
+                    children.sort_by(|(left_name, left_size, _), (right_name, right_size, _)| {
+                        right_size.cmp(left_size).then(left_name.cmp(right_name))
+                    });
+                } else {
+                    children.sort_by_key(|(name, ..)| *name);
+                }
+                stack.extend(
+                    children
+                        .into_iter()
+                        .rev()
+                        .map(|(_, _, entry)| (depth + 1, entry)),
+                );
+            }
+        }
+    }
+    lines.join("\n")
+}
+
+fn children<'a>(
+    filesystem: &'a FileSystem,
+    id: DirectoryId,
+    sizes: &BTreeMap<DirectoryId, u64>,
+) -> Vec<(&'a str, u64, Entry<'a>)> {
+    let directory = filesystem.directory(id);
+    let subdirectories = directory.subdirectories().map(|subdirectory| {
+        (
+            filesystem.directory(subdirectory).name(),
+            sizes[&subdirectory],
+            Entry::Directory(subdirectory),
+        )
+    });
+    let files = directory.files().iter().map(|file| {
+        (
+            file.name.as_str(),
+            file.size,
+            Entry::File(&file.name, file.size),
+        )
+    });
+    subdirectories.chain(files).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{human_size, tree, RenderOptions};
+    use crate::challenges::day_07::example;
+
+    #[test]
+    fn test_tree() {
+        assert_eq!(
+            tree(&example(), RenderOptions::default()),
+            "- / (dir, 48381165)
+  - a (dir, 94853)
+    - e (dir, 584)
+      - i (file, 584)
+    - f (file, 29116)
+    - g (file, 2557)
+    - h.lst (file, 62596)
+  - b.txt (file, 14848514)
+  - c.dat (file, 8504156)
+  - d (dir, 24933642)
+    - d.ext (file, 5626152)
+    - d.log (file, 8033020)
+    - j (file, 4060174)
+    - k (file, 7214296)"
+        );
+    }
+
+    #[test]
+    fn test_tree_sorted_by_size() {
+        let options = RenderOptions {
+            human_readable: true,
+            sort_by_size: true,
+        };
+        assert_eq!(
+            tree(&example(), options),
+            "- / (dir, 46.1M)
+  - d (dir, 23.8M)
+    - d.log (file, 7.7M)
+    - k (file, 6.9M)
+    - d.ext (file, 5.4M)
+    - j (file, 3.9M)
+  - b.txt (file, 14.2M)
+  - c.dat (file, 8.1M)
+  - a (dir, 92.6K)
+    - h.lst (file, 61.1K)
+    - f (file, 28.4K)
+    - g (file, 2.5K)
+    - e (dir, 584B)
+      - i (file, 584B)"
+        );
+    }
+
+    #[test]
+    fn test_human_size() {
+        assert_eq!(human_size(0), "0B");
+        assert_eq!(human_size(1023), "1023B");
+        assert_eq!(human_size(1024), "1.0K");
+        assert_eq!(human_size(70000000), "66.8M");
+        assert_eq!(human_size(u64::from(u32::MAX)), "4.0G");
+        assert_eq!(human_size(5 << 40), "5.0T");
+        assert_eq!(human_size(u64::MAX), "16.0E");
+    }
+}
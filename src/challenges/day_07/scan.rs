@@ -0,0 +1,79 @@
+use super::filesystem::{DirectoryId, FileSystem};
+use crate::error::{Error, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn io_error(path: &Path, err: std::io::Error) -> Error {
+    Error::Io {
+        message: format!("could not read {}: {err}", path.display()),
+    }
+}
+
+fn scan_directory(
+    path: &Path,
+    filesystem: &mut FileSystem,
+    id: DirectoryId,
+    pending: &mut Vec<(PathBuf, DirectoryId)>,
+) -> Result<()> {
+    let entries = fs::read_dir(path).map_err(|err| io_error(path, err))?;
+    for entry in entries {
+        let entry = entry.map_err(|err| io_error(path, err))?;
+        let entry_path = entry.path();
+        let name = entry.file_name().into_string().map_err(|_| {
+            Error::invalid(7, &format!("{} is not valid UTF-8", entry_path.display()))
+        })?;
+        if name.contains('\n') {
+            return Err(Error::invalid(
+                7,
+                &format!("{} contains a line break", entry_path.display()),
+            ));
+        }
+        // Symbolic links are skipped instead of followed, they could lead out of the tree.
+        let file_type = entry
+            .file_type()
+            .map_err(|err| io_error(&entry_path, err))?;
+        if file_type.is_dir() {
+            let subdirectory = filesystem.add_directory(id, &name)?;
+            pending.push((entry_path, subdirectory));
+        } else if file_type.is_file() {
+            let size = entry
+                .metadata()
+                .map_err(|err| io_error(&entry_path, err))?
+                .len();
+            filesystem.add_file(id, &name, size)?;
+        }
+    }
+    Ok(())
+}
+
+/// Reads a directory on disk and everything below it into a [`FileSystem`].
+pub fn scan(root: &Path) -> Result<FileSystem> {
+    let mut filesystem = FileSystem::new();
+    let mut pending = vec![(root.to_path_buf(), FileSystem::ROOT)];
+    while let Some((path, id)) = pending.pop() {
+        scan_directory(&path, &mut filesystem, id, &mut pending)?;
+    }
+    Ok(filesystem)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::scan;
+    use crate::challenges::day_07::example;
+    use crate::challenges::day_07::materialise::materialise;
+    use crate::challenges::day_07::transcript::transcript;
+    use std::fs;
+
+    #[test]
+    fn test_round_trip_through_disk() {
+        let filesystem = example();
+        let target = std::env::temp_dir().join(format!("aoc_2022_scan_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&target);
+
+        materialise(&filesystem, &target).unwrap();
+        let scanned = scan(&target).unwrap();
+        fs::remove_dir_all(&target).unwrap();
+
+        assert_eq!(transcript(&scanned), transcript(&filesystem));
+    }
+}
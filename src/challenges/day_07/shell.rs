@@ -0,0 +1,213 @@
+use super::filesystem::FileSystem;
+use super::query::{self, Filter, Query};
+use super::transcript::Line;
+use super::Terminal;
+use std::mem;
+
+pub const HELP: &str = "commands:
+  cd <path>       change to a directory, like /, .., a or ../b/c
+  ls              list the current directory
+  pwd             print the current directory
+  du              print the size of every directory below the current one
+  find [filter]   list entries below the current one, e.g. find size > 1M and type = file
+  help            print this help
+  exit            leave the shell";
+
+/// Interactive shell over an explored file system.
+///
+/// Navigation goes through [`Terminal`], but unlike a transcript the shell refuses to enter
+/// directories that do not exist.
+pub struct Shell {
+    terminal: Terminal,
+}
+
+impl Shell {
+    pub fn new(filesystem: FileSystem) -> Self {
+        Shell {
+            terminal: Terminal::with_filesystem(filesystem),
+        }
+    }
+
+    pub fn pwd(&self) -> String {
+        self.terminal
+            .filesystem()
+            .path(self.terminal.current_directory())
+    }
+
+    fn components(path: &str) -> impl Iterator<Item = &str> {
+        path.split('/')
+            .filter(|name| !name.is_empty() && *name != ".")
+    }
+
+    /// Whether every directory along the path exists, without moving.
+    fn exists(&self, path: &str) -> bool {
+        let filesystem = self.terminal.filesystem();
+        let start = if path.starts_with('/') {
+            FileSystem::ROOT
+        } else {
+            self.terminal.current_directory()
+        };
+        Self::components(path)
+            .try_fold(start, |current, name| match name {
+                ".." => Some(filesystem.directory(current).parent().unwrap_or(current)),
+                name => filesystem.directory(current).subdirectory(name),
+            })
+            .is_some()
+    }
+
+    fn change_directory(&mut self, path: &str) -> Result<String, String> {
+        if !self.exists(path) {
+            return Err(format!("cd: no such directory: {path}"));
+        }
+        let mut terminal = mem::take(&mut self.terminal);
+        if path.starts_with('/') {
+            terminal = terminal.change_root();
+        }
+        self.terminal = Self::components(path)
+            .try_fold(terminal, |terminal, name| match name {
+                ".." => Ok(terminal.change_up()),
+                name => terminal.change_directory(name),
+            })
+            .expect("every directory along the path exists");
+        Ok(String::new())
+    }
+
+    fn list(&self) -> String {
+        let filesystem = self.terminal.filesystem();
+        let directory = filesystem.directory(self.terminal.current_directory());
+        let mut lines: Vec<(&str, Line)> = directory
+            .subdirectories()
+            .map(|id| {
+                let name = filesystem.directory(id).name();
+                (name, Line::Directory(name.to_string()))
+            })
+            .chain(directory.files().iter().map(|file| {
+                let line = Line::File {
+                    name: file.name.clone(),
+                    size: file.size,
+                };
+                (file.name.as_str(), line)
+            }))
+            .collect();
+        lines.sort_by_key(|(name, _)| *name);
+        lines
+            .into_iter()
+            .map(|(_, line)| line.to_string())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// Entries below the current directory, including it.
+    fn entries(&self, filter: Option<Filter>) -> Vec<query::Entry> {
+        let pwd = self.pwd();
+        // Everything below the current directory starts with its path and a slash.
+        let prefix = self
+            .terminal
+            .filesystem()
+            .child_path(self.terminal.current_directory(), "");
+        query::run(
+            self.terminal.filesystem(),
+            &Query {
+                filter,
+                largest: None,
+            },
+        )
+        .into_iter()
+        .filter(|entry| entry.path == pwd || entry.path.starts_with(&prefix))
+        .collect()
+    }
+
+    fn disk_usage(&self) -> String {
+        let mut directories: Vec<query::Entry> = self
+            .entries(None)
+            .into_iter()
+            .filter(|entry| entry.kind == query::Kind::Directory)
+            .collect();
+        // Like `du`, every directory comes after its subdirectories.
+        directories.reverse();
+        directories
+            .into_iter()
+            .map(|entry| format!("{}\t{}", entry.size, entry.path))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    fn find(&self, filter: &str) -> Result<String, String> {
+        let filter = match filter {
+            "" => None,
+            filter => Some(
+                filter
+                    .parse::<Filter>()
+                    .map_err(|err| format!("find: {err}"))?,
+            ),
+        };
+        Ok(self
+            .entries(filter)
+            .into_iter()
+            .map(|entry| entry.path)
+            .collect::<Vec<String>>()
+            .join("\n"))
+    }
+
+    /// Runs a single command line and returns what it prints.
+    pub fn execute(&mut self, command: &str) -> Result<String, String> {
+        let command = command.trim();
+        let (name, argument) = command.split_once(' ').unwrap_or((command, ""));
+        let argument = argument.trim();
+        match (name, argument) {
+            ("cd", "") => self.change_directory("/"),
+            ("cd", path) => self.change_directory(path),
+            ("ls", "") => Ok(self.list()),
+            ("pwd", "") => Ok(self.pwd()),
+            ("du", "") => Ok(self.disk_usage()),
+            ("find", filter) => self.find(filter),
+            ("help", _) => Ok(HELP.to_string()),
+            ("", _) => Ok(String::new()),
+            ("ls" | "pwd" | "du", _) => Err(format!("{name}: takes no arguments")),
+            (name, _) => Err(format!("{name}: command not found, try help")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Shell;
+    use crate::challenges::day_07::example;
+
+    #[test]
+    fn test_navigation() {
+        let mut shell = Shell::new(example());
+        assert_eq!(shell.execute("cd a/e"), Ok(String::new()));
+        assert_eq!(shell.execute("pwd"), Ok("/a/e".to_string()));
+        assert_eq!(shell.execute("cd ../../d"), Ok(String::new()));
+        assert_eq!(shell.pwd(), "/d");
+        assert_eq!(
+            shell.execute("cd /a/x"),
+            Err("cd: no such directory: /a/x".to_string())
+        );
+        assert_eq!(shell.pwd(), "/d");
+        assert_eq!(shell.execute("cd .."), Ok(String::new()));
+        assert_eq!(shell.execute("cd .."), Ok(String::new()));
+        assert_eq!(shell.pwd(), "/");
+    }
+
+    #[test]
+    fn test_listing_and_sizes() {
+        let mut shell = Shell::new(example());
+        assert_eq!(
+            shell.execute("ls"),
+            Ok("dir a\n14848514 b.txt\n8504156 c.dat\ndir d".to_string())
+        );
+        shell.execute("cd a").unwrap();
+        assert_eq!(shell.execute("du"), Ok("584\t/a/e\n94853\t/a".to_string()));
+        assert_eq!(
+            shell.execute("find type = file"),
+            Ok("/a/e/i\n/a/f\n/a/g\n/a/h.lst".to_string())
+        );
+        assert!(shell.execute("find size >").is_err());
+        assert_eq!(
+            shell.execute("rm f"),
+            Err("rm: command not found, try help".to_string())
+        );
+    }
+}
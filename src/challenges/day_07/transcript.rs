@@ -0,0 +1,339 @@
+use super::filesystem::{invalid_name, DirectoryId, FileSystem};
+use crate::error::{Error, Result};
+use std::fmt;
+use std::str::FromStr;
+
+/// Where a `$ cd` command goes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Target {
+    Root,
+    Parent,
+    Child(String),
+}
+
+/// A single line of a terminal transcript, either a command or a line of `ls` output.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Line {
+    ChangeDirectory(Target),
+    List,
+    Directory(String),
+    File { name: String, size: u64 },
+}
+
+impl Line {
+    pub fn is_output(&self) -> bool {
+        matches!(self, Line::Directory(_) | Line::File { .. })
+    }
+}
+
+/// Checks a name of the line `s` that starts at the given column, so an invalid name is
+/// reported at its column instead of later by the file system.
+fn check_name(s: &str, column: usize, name: &str) -> Result<()> {
+    match invalid_name(name) {
+        Some((offset, message)) => Err(Error::parse(7, 1, column + offset, s, message)),
+        None => Ok(()),
+    }
+}
+
+fn parse_command(s: &str) -> Result<Line> {
+    let command = &s[2..];
+    let (name, argument) = match command.split_once(' ') {
+        Some((name, argument)) => (name, Some(argument)),
+        None => (command, None),
+    };
+    match (name, argument) {
+        ("cd", Some("/")) => Ok(Line::ChangeDirectory(Target::Root)),
+        ("cd", Some("..")) => Ok(Line::ChangeDirectory(Target::Parent)),
+        ("cd", Some(directory)) if !directory.is_empty() => {
+            check_name(s, 6, directory)?;
+            Ok(Line::ChangeDirectory(Target::Child(directory.to_string())))
+        }
+        ("cd", _) => Err(Error::parse(7, 1, s.len() + 1, s, "expected a directory")),
+        ("ls", None) => Ok(Line::List),
+        ("ls", Some(_)) => Err(Error::parse(7, 1, 6, s, "ls takes no arguments")),
+        (name, _) => Err(Error::parse(
+            7,
+            1,
+            3,
+            s,
+            &format!("unknown command `{name}`"),
+        )),
+    }
+}
+
+fn parse_output(s: &str) -> Result<Line> {
+    let expected = "expected `dir <name>` or `<size> <name>`";
+    let (first, name) = s
+        .split_once(' ')
+        .ok_or_else(|| Error::parse(7, 1, 1, s, expected))?;
+    if name.is_empty() {
+        return Err(Error::parse(7, 1, s.len() + 1, s, "expected a name"));
+    }
+    check_name(s, first.len() + 2, name)?;
+    if first == "dir" {
+        return Ok(Line::Directory(name.to_string()));
+    }
+    if first.is_empty() || !first.bytes().all(|byte| byte.is_ascii_digit()) {
+        return Err(Error::parse(7, 1, 1, s, expected));
+    }
+    let size = first
+        .parse::<u64>()
+        .map_err(|_| Error::parse(7, 1, 1, s, "file size is too large"))?;
+    Ok(Line::File {
+        name: name.to_string(),
+        size,
+    })
+}
+
+impl FromStr for Line {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if s.starts_with("$ ") {
+            parse_command(s)
+        } else {
+            parse_output(s)
+        }
+    }
+}
+
+impl fmt::Display for Line {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Line::ChangeDirectory(Target::Root) => write!(f, "$ cd /"),
+            Line::ChangeDirectory(Target::Parent) => write!(f, "$ cd .."),
+            Line::ChangeDirectory(Target::Child(name)) => write!(f, "$ cd {name}"),
+            Line::List => write!(f, "$ ls"),
+            Line::Directory(name) => write!(f, "dir {name}"),
+            Line::File { name, size } => write!(f, "{size} {name}"),
+        }
+    }
+}
+
+/// Step of the walk that writes a transcript.
+enum Step {
+    Enter(DirectoryId),
+    Leave,
+}
+
+fn explore(filesystem: &FileSystem, lines: &mut Vec<Line>) {
+    let mut stack = vec![Step::Enter(FileSystem::ROOT)];
+    while let Some(step) = stack.pop() {
+        let id = match step {
+            Step::Enter(id) => id,
+            Step::Leave => {
+                lines.push(Line::ChangeDirectory(Target::Parent));
+                continue;
+            }
+        };
+        let directory = filesystem.directory(id);
+        if id != FileSystem::ROOT {
+            let name = directory.name().to_string();
+            lines.push(Line::ChangeDirectory(Target::Child(name)));
+        }
+        let mut listing: Vec<Line> = directory
+            .subdirectories()
+            .map(|subdirectory| {
+                Line::Directory(filesystem.directory(subdirectory).name().to_string())
+            })
+            .chain(directory.files().iter().map(|file| Line::File {
+                name: file.name.clone(),
+                size: file.size,
+            }))
+            .collect();
+        listing.sort_by(|left, right| entry_name(left).cmp(entry_name(right)));
+
+        lines.push(Line::List);
+        lines.extend(listing);
+        let subdirectories: Vec<DirectoryId> = directory.subdirectories().collect();
+        for subdirectory in subdirectories.into_iter().rev() {
+            stack.push(Step::Leave);
+            stack.push(Step::Enter(subdirectory));
+        }
+    }
+}
+
+fn entry_name(line: &Line) -> &str {
+    match line {
+        Line::Directory(name) | Line::File { name, .. } => name,
+        _ => "",
+    }
+}
+
+/// Writes a transcript that explores the whole file system, the inverse of parsing one.
+///
+/// Every directory is listed once and entered in name order, like in the puzzle example.
+pub fn transcript(filesystem: &FileSystem) -> String {
+    let mut lines = vec![Line::ChangeDirectory(Target::Root)];
+    explore(filesystem, &mut lines);
+    while lines.last() == Some(&Line::ChangeDirectory(Target::Parent)) {
+        lines.pop();
+    }
+    lines
+        .iter()
+        .map(|line| line.to_string())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{transcript, Line, Target};
+    use crate::challenges::day_07::parse_input;
+    use crate::challenges::day_07::{deep_tree, example};
+    use crate::error::Error;
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(
+            "$ cd /".parse::<Line>(),
+            Ok(Line::ChangeDirectory(Target::Root))
+        );
+        assert_eq!(
+            "$ cd ..".parse::<Line>(),
+            Ok(Line::ChangeDirectory(Target::Parent))
+        );
+        assert_eq!(
+            "$ cd my-dir.old v2".parse::<Line>(),
+            Ok(Line::ChangeDirectory(Target::Child(
+                "my-dir.old v2".to_string()
+            )))
+        );
+        assert_eq!("$ ls".parse::<Line>(), Ok(Line::List));
+        assert_eq!(
+            "$ rm -rf a".parse::<Line>(),
+            Err(Error::parse(7, 1, 3, "$ rm -rf a", "unknown command `rm`"))
+        );
+        assert!("$ cd".parse::<Line>().is_err());
+        assert_eq!(
+            "$ cd a/b".parse::<Line>(),
+            Err(Error::parse(
+                7,
+                1,
+                7,
+                "$ cd a/b",
+                "names cannot contain `/`"
+            ))
+        );
+        assert_eq!(
+            "$ cd .".parse::<Line>(),
+            Err(Error::parse(
+                7,
+                1,
+                6,
+                "$ cd .",
+                "`.` and `..` cannot name a file or directory"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_output() {
+        assert_eq!(
+            "dir a-b.c".parse::<Line>(),
+            Ok(Line::Directory("a-b.c".to_string()))
+        );
+        assert_eq!(
+            "584 i".parse::<Line>(),
+            Ok(Line::File {
+                name: "i".to_string(),
+                size: 584
+            })
+        );
+        assert_eq!(
+            "12x i".parse::<Line>(),
+            Err(Error::parse(
+                7,
+                1,
+                1,
+                "12x i",
+                "expected `dir <name>` or `<size> <name>`"
+            ))
+        );
+        assert_eq!(
+            "99999999999999999999 big".parse::<Line>(),
+            Err(Error::parse(
+                7,
+                1,
+                1,
+                "99999999999999999999 big",
+                "file size is too large"
+            ))
+        );
+        assert_eq!(
+            "dir a/b".parse::<Line>(),
+            Err(Error::parse(7, 1, 6, "dir a/b", "names cannot contain `/`"))
+        );
+        assert_eq!(
+            "dir a\\b".parse::<Line>(),
+            Err(Error::parse(
+                7,
+                1,
+                6,
+                "dir a\\b",
+                "names cannot contain `\\`"
+            ))
+        );
+        assert_eq!(
+            "12 /x".parse::<Line>(),
+            Err(Error::parse(7, 1, 4, "12 /x", "names cannot contain `/`"))
+        );
+        assert_eq!(
+            "dir ..".parse::<Line>(),
+            Err(Error::parse(
+                7,
+                1,
+                5,
+                "dir ..",
+                "`.` and `..` cannot name a file or directory"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_transcript() {
+        let mut filesystem = example();
+        let a = filesystem.lookup("/a").unwrap();
+        filesystem.add_directory(a, "empty dir").unwrap();
+
+        let expected = "$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+dir empty dir
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd empty dir
+$ ls
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+5626152 d.ext
+8033020 d.log
+4060174 j
+7214296 k";
+        assert_eq!(transcript(&filesystem), expected);
+
+        let parsed = parse_input(expected.lines().collect()).unwrap();
+        assert_eq!(transcript(&parsed), expected);
+    }
+
+    #[test]
+    fn test_transcript_of_deep_tree() {
+        let filesystem = deep_tree(20000);
+        let written = transcript(&filesystem);
+        assert!(written.ends_with("$ cd d\n$ ls\n7 f"));
+        assert_eq!(parse_input(written.lines().collect()), Ok(filesystem));
+    }
+}
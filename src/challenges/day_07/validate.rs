@@ -0,0 +1,126 @@
+use super::filesystem::{DirectoryId, FileSystem};
+use super::transcript::{Line, Target};
+use crate::error::Error;
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    /// The transcript is odd, but still describes a single file system.
+    Warning,
+    /// The transcript contradicts itself.
+    Error,
+}
+
+/// Finding of the consistency checks, `line` is one based.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub line: usize,
+    pub text: String,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn into_error(self) -> Error {
+        Error::parse(7, self.line, 1, &self.text, &self.message)
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(f, "line {}: {severity}: {}", self.line, self.message)
+    }
+}
+
+/// Remembers what a transcript has shown so far, to notice when it contradicts itself.
+#[derive(Default)]
+pub struct Checker {
+    listed: HashMap<DirectoryId, usize>,
+    files: HashMap<(DirectoryId, String), (u64, usize)>,
+}
+
+impl Checker {
+    /// Checks a line before it is executed in `current_directory`.
+    pub fn check(
+        &mut self,
+        filesystem: &FileSystem,
+        current_directory: DirectoryId,
+        line: &Line,
+        number: usize,
+        text: &str,
+    ) -> Option<Diagnostic> {
+        let diagnostic = |severity, message: String| Diagnostic {
+            severity,
+            line: number,
+            text: text.to_string(),
+            message,
+        };
+        match line {
+            Line::ChangeDirectory(Target::Parent) if current_directory == FileSystem::ROOT => {
+                Some(diagnostic(
+                    Severity::Warning,
+                    "`cd ..` at the root stays at the root".to_string(),
+                ))
+            }
+            Line::List => {
+                let first = *self.listed.entry(current_directory).or_insert(number);
+                (first != number).then(|| {
+                    diagnostic(
+                        Severity::Warning,
+                        format!(
+                            "{} was already listed on line {first}",
+                            filesystem.path(current_directory)
+                        ),
+                    )
+                })
+            }
+            Line::Directory(name) | Line::ChangeDirectory(Target::Child(name)) => self
+                .files
+                .get(&(current_directory, name.clone()))
+                .map(|(_, first_line)| {
+                    diagnostic(
+                        Severity::Error,
+                        format!(
+                            "{} is a file on line {first_line}, not a directory",
+                            filesystem.child_path(current_directory, name)
+                        ),
+                    )
+                }),
+            Line::File { name, .. }
+                if filesystem
+                    .directory(current_directory)
+                    .subdirectory(name)
+                    .is_some() =>
+            {
+                Some(diagnostic(
+                    Severity::Error,
+                    format!(
+                        "{} is a directory, not a file",
+                        filesystem.child_path(current_directory, name)
+                    ),
+                ))
+            }
+            Line::File { name, size } => {
+                let (first_size, first_line) = *self
+                    .files
+                    .entry((current_directory, name.clone()))
+                    .or_insert((*size, number));
+                (first_size != *size).then(|| {
+                    diagnostic(
+                        Severity::Error,
+                        format!(
+                            "{} has size {size}, but size {first_size} on line {first_line}",
+                            filesystem.child_path(current_directory, name)
+                        ),
+                    )
+                })
+            }
+            _ => None,
+        }
+    }
+}
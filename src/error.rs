@@ -0,0 +1,165 @@
+use crate::challenges::Part;
+use serde::Serialize;
+use std::fmt;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Everything that can go wrong between reading a puzzle input and printing its answer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Error {
+    /// The input does not have the expected format. `line` and `column` are one based and
+    /// point into `text`, the offending input line.
+    Parse {
+        day: u8,
+        line: usize,
+        column: usize,
+        text: String,
+        message: String,
+    },
+    /// The input could be parsed, but does not describe a puzzle that can be solved.
+    Invalid {
+        day: u8,
+        message: String,
+    },
+    NotImplemented {
+        day: u8,
+        part: Part,
+    },
+    /// A filter expression like the one of `fs find --where` is malformed. `column` is one based
+    /// and points into `text`, the whole expression.
+    Filter {
+        column: usize,
+        text: String,
+        message: String,
+    },
+    /// The puzzle input could not be read, or a file could not be written.
+    Io {
+        message: String,
+    },
+}
+
+impl Error {
+    pub fn parse(day: u8, line: usize, column: usize, text: &str, message: &str) -> Self {
+        Error::Parse {
+            day,
+            line,
+            column,
+            text: text.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    pub fn filter(column: usize, text: &str, message: &str) -> Self {
+        Error::Filter {
+            column,
+            text: text.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    pub fn invalid(day: u8, message: &str) -> Self {
+        Error::Invalid {
+            day,
+            message: message.to_string(),
+        }
+    }
+
+    /// Moves a parse error of a single line to the given line of the whole input.
+    pub fn on_line(self, line: usize) -> Self {
+        match self {
+            Error::Parse {
+                day,
+                column,
+                text,
+                message,
+                ..
+            } => Error::Parse {
+                day,
+                line,
+                column,
+                text,
+                message,
+            },
+            other => other,
+        }
+    }
+
+    /// Multi line rendering for the terminal, pointing at the offending column of a parse error.
+    pub fn render(&self) -> String {
+        match self {
+            Error::Parse { column, text, .. } | Error::Filter { column, text, .. } => {
+                let indent = " ".repeat(column.saturating_sub(1));
+                format!("{self}\n    {text}\n    {indent}^")
+            }
+            _ => self.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse {
+                day,
+                line,
+                column,
+                message,
+                ..
+            } => write!(f, "day {day}, line {line}, column {column}: {message}"),
+            Error::Invalid { day, message } => write!(f, "day {day}: {message}"),
+            Error::NotImplemented { day, part } => {
+                write!(f, "day {day} part {} is not implemented", part.number())
+            }
+            Error::Filter {
+                column, message, ..
+            } => write!(f, "filter, column {column}: {message}"),
+            Error::Io { message } => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::Error;
+    use crate::challenges::Part;
+
+    #[test]
+    fn test_render_parse_error() {
+        let error = Error::parse(4, 1, 3, "2-x,6-8", "expected a number").on_line(12);
+        assert_eq!(
+            error.render(),
+            "day 4, line 12, column 3: expected a number
+    2-x,6-8
+      ^"
+        );
+    }
+
+    #[test]
+    fn test_render_filter_error() {
+        assert_eq!(
+            Error::filter(8, "size > lots", "expected a number").render(),
+            "filter, column 8: expected a number
+    size > lots
+           ^"
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            Error::NotImplemented {
+                day: 7,
+                part: Part::Two
+            }
+            .to_string(),
+            "day 7 part 2 is not implemented"
+        );
+        assert_eq!(
+            Error::invalid(6, "no start-of-packet marker").render(),
+            "day 6: no start-of-packet marker"
+        );
+    }
+}
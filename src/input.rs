@@ -0,0 +1,52 @@
+use crate::error::{Error, Result};
+use std::fs::File;
+use std::io::{self, Read};
+
+/// Path that stands for standard input on the command line.
+pub const STDIN: &str = "-";
+
+/// Reads a whole puzzle input from any reader.
+pub fn read<R: Read>(mut reader: R) -> Result<String> {
+    let mut contents = String::new();
+    reader
+        .read_to_string(&mut contents)
+        .map_err(|err| Error::Io {
+            message: err.to_string(),
+        })?;
+    Ok(contents)
+}
+
+/// Opens a puzzle input by path, where `-` reads from standard input.
+pub fn open(path: &str) -> Result<Box<dyn Read>> {
+    if path == STDIN {
+        return Ok(Box::new(io::stdin()));
+    }
+    let file = File::open(path).map_err(|err| Error::Io {
+        message: format!("could not read {path}: {err}"),
+    })?;
+    Ok(Box::new(file))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{open, read};
+    use crate::error::Error;
+
+    #[test]
+    fn test_read_from_slice() {
+        assert_eq!(read("1\n2\n".as_bytes()), Ok("1\n2\n".to_string()));
+    }
+
+    #[test]
+    fn test_read_invalid_utf8() {
+        assert!(matches!(read(&[0xff, 0xfe][..]), Err(Error::Io { .. })));
+    }
+
+    #[test]
+    fn test_open_missing_file() {
+        assert!(matches!(
+            open("data/does_not_exist.txt"),
+            Err(Error::Io { .. })
+        ));
+    }
+}
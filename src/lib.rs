@@ -0,0 +1,7 @@
+//! Solutions to [Advent of Code 2022](https://adventofcode.com/), one module per day under [`challenges`].
+
+pub mod answers;
+pub mod bench;
+pub mod challenges;
+pub mod error;
+pub mod input;
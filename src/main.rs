@@ -1,12 +1,603 @@
-use std::fs;
+use aoc_2022::answers::{self, Answers, Verdict};
+use aoc_2022::bench::{self, Stats};
+use aoc_2022::challenges::day_05::{self, Day05};
+use aoc_2022::challenges::day_07::diff::{diff, Change};
+use aoc_2022::challenges::day_07::json;
+use aoc_2022::challenges::day_07::materialise::materialise;
+use aoc_2022::challenges::day_07::query::{self, Filter, Kind, Query};
+use aoc_2022::challenges::day_07::render::{self, RenderOptions};
+use aoc_2022::challenges::day_07::scan::scan;
+use aoc_2022::challenges::day_07::shell::Shell;
+use aoc_2022::challenges::day_07::transcript::transcript;
+use aoc_2022::challenges::day_07::validate::Severity;
+use aoc_2022::challenges::day_07::{self, filesystem::FileSystem, Day07, DiskLimits};
+use aoc_2022::challenges::{self, Part, Puzzle, Solution};
+use aoc_2022::error::Error;
+use aoc_2022::input;
+use clap::{Parser, Subcommand, ValueEnum};
+use report::Row;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
 
-mod challenges;
+mod report;
 
-fn main() {
-    let contents: String =
-        fs::read_to_string("data/day_07.txt").expect("Should have been able to read file");
+#[derive(Parser)]
+#[command(name = "aoc_2022", about = "Solutions to Advent of Code 2022")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Text,
+    /// One JSON object per line and part
+    Json,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run a single part of a day, or every implemented day, against the puzzle input
+    Run {
+        #[arg(short, long, required_unless_present = "all")]
+        day: Option<u8>,
+        #[arg(short, long, default_value_t = 1, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+        /// Path to the puzzle input or - for standard input, defaults to data/day_NN.txt
+        #[arg(short, long, conflicts_with = "all")]
+        input: Option<String>,
+        /// Same as --input, e.g. `run -d 6 -` to read from standard input
+        #[arg(value_name = "INPUT", conflicts_with_all = ["all", "input"])]
+        input_path: Option<String>,
+        /// Run both parts of every implemented day and print a summary table
+        #[arg(short, long, conflicts_with_all = ["day", "part"])]
+        all: bool,
+        #[arg(short, long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+        /// Size of the disk in day 7 part 2, defaults to 70000000
+        #[arg(long, value_name = "BYTES", conflicts_with = "all")]
+        capacity: Option<u64>,
+        /// Free space the update in day 7 part 2 needs, defaults to 30000000
+        #[arg(long, value_name = "BYTES", conflicts_with = "all")]
+        required_free: Option<u64>,
+    },
+    /// Measure parse and solve time of a single part over many runs
+    Bench {
+        #[arg(short, long)]
+        day: u8,
+        #[arg(short, long, default_value_t = 1, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+        /// Number of measured runs
+        #[arg(short = 'n', long, default_value_t = 100, value_parser = clap::value_parser!(u64).range(1..))]
+        iterations: u64,
+        /// Number of runs before measuring
+        #[arg(short, long, default_value_t = 10)]
+        warmup: u64,
+        /// Path to the puzzle input or - for standard input, defaults to data/day_NN.txt
+        #[arg(value_name = "INPUT")]
+        input: Option<String>,
+    },
+    /// Compare the answers of every implemented day with the recorded ones
+    Verify {
+        /// Path to the recorded answers
+        #[arg(long, default_value = answers::DEFAULT_PATH)]
+        answers: String,
+    },
+    /// Print every move of the day 5 crane with the stacks before and after it
+    Crane {
+        /// Part 2 moves several crates at once, like the CrateMover 9001
+        #[arg(short, long, default_value_t = 1, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+        /// Only print the stacks after the last move, in the notation of the puzzle input
+        #[arg(long = "final")]
+        final_only: bool,
+        /// Path to the puzzle input or - for standard input, defaults to data/day_05.txt
+        #[arg(value_name = "INPUT")]
+        input: Option<String>,
+    },
+    /// Inspect the file system reconstructed from a day 7 terminal transcript
+    Fs {
+        #[command(subcommand)]
+        command: FsCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum FsCommand {
+    /// Print the file system as a tree with file sizes and cumulative directory sizes
+    Tree {
+        /// Print sizes in binary units like 46.1M
+        #[arg(short = 'H', long)]
+        human_readable: bool,
+        /// List the biggest entries of every directory first
+        #[arg(short, long)]
+        sort_by_size: bool,
+        /// Path to the transcript or - for standard input, defaults to data/day_07.txt
+        #[arg(value_name = "INPUT")]
+        input: Option<String>,
+    },
+    /// Recreate the file system in a directory, with sparse files of the listed sizes
+    Materialise {
+        /// Directory to create the file system in, nothing is written outside of it
+        target: PathBuf,
+        /// Path to the transcript or - for standard input, defaults to data/day_07.txt
+        #[arg(value_name = "INPUT")]
+        input: Option<String>,
+    },
+    /// List the files and directories matching a filter, with their sizes
+    Find {
+        /// Filter like `size > 1M and depth <= 2`, on size, depth, name, ext and type
+        #[arg(short, long = "where", value_name = "FILTER")]
+        filter: Option<Filter>,
+        /// Only list this many entries, biggest first
+        #[arg(short, long, value_name = "N")]
+        largest: Option<usize>,
+        /// Print sizes in binary units like 46.1M
+        #[arg(short = 'H', long)]
+        human_readable: bool,
+        /// Path to the transcript or - for standard input, defaults to data/day_07.txt
+        #[arg(value_name = "INPUT")]
+        input: Option<String>,
+    },
+    /// Compare two transcripts, listing added, removed and resized files and directories
+    Diff {
+        /// Transcript of the earlier snapshot
+        before: String,
+        /// Transcript of the later snapshot
+        after: String,
+        /// Print sizes in binary units like 46.1M
+        #[arg(short = 'H', long)]
+        human_readable: bool,
+    },
+    /// Report lines of the transcript that contradict earlier ones
+    Check {
+        /// Path to the transcript or - for standard input, defaults to data/day_07.txt
+        #[arg(value_name = "INPUT")]
+        input: Option<String>,
+    },
+    /// Explore the file system interactively with cd, ls, pwd, du and find
+    Shell {
+        /// Path to the transcript, defaults to data/day_07.txt
+        #[arg(value_name = "INPUT")]
+        input: Option<String>,
+    },
+    /// Print the file system as JSON, with the cumulative size of every directory
+    Export {
+        /// Path to the transcript or - for standard input, defaults to data/day_07.txt
+        #[arg(value_name = "INPUT")]
+        input: Option<String>,
+    },
+    /// Print a transcript exploring a directory on disk, in the format day 7 reads
+    Transcript {
+        /// Directory to explore, symbolic links are skipped
+        directory: PathBuf,
+    },
+}
+
+fn default_input_path(day: u8) -> String {
+    format!("data/day_{day:02}.txt")
+}
+
+fn run_single(
+    day: u8,
+    part: u8,
+    input: Option<String>,
+    limits: (Option<u64>, Option<u64>),
+    format: Format,
+) -> ExitCode {
+    let part = Part::from_number(part).expect("part is validated by the argument parser");
+    let puzzle: Option<Box<dyn Puzzle>> = match limits {
+        (None, None) => challenges::find(day),
+        (capacity, required_free) if day == 7 => {
+            let default = DiskLimits::default();
+            let limits = DiskLimits {
+                capacity: capacity.unwrap_or(default.capacity),
+                required_free: required_free.unwrap_or(default.required_free),
+            };
+            Some(Box::new(Day07 { limits }))
+        }
+        _ => {
+            eprintln!("--capacity and --required-free only apply to day 7");
+            return ExitCode::FAILURE;
+        }
+    };
+    let path = input.unwrap_or_else(|| default_input_path(day));
+    let outcome = match puzzle {
+        Some(puzzle) => input::open(&path)
+            .and_then(input::read)
+            .map(|contents| puzzle.run(&contents, part)),
+        None => Err(Error::NotImplemented { day, part }),
+    };
+    let row = Row { day, part, outcome };
+
+    let succeeded = matches!(&row.outcome, Ok(run) if run.answer.is_ok());
+    match format {
+        Format::Json => println!("{}", report::json_line(&row)),
+        Format::Text => match row.outcome.and_then(|run| run.answer) {
+            Ok(answer) => println!("{answer}"),
+            Err(err) => eprintln!("{}", err.render()),
+        },
+    }
+    if succeeded {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn run_all(format: Format) -> ExitCode {
+    let mut rows: Vec<Row> = Vec::new();
+    for puzzle in challenges::registry() {
+        let day = puzzle.day();
+        let contents = input::open(&default_input_path(day)).and_then(input::read);
+        for part in Part::ALL {
+            let outcome = match &contents {
+                Ok(contents) => Ok(puzzle.run(contents, part)),
+                Err(err) => Err(err.clone()),
+            };
+            rows.push(Row { day, part, outcome });
+        }
+    }
+    match format {
+        Format::Text => println!("{}", report::run_table(&rows)),
+        Format::Json => {
+            for row in rows.iter() {
+                println!("{}", report::json_line(row));
+            }
+        }
+    }
+    // Used as a smoke test, so a single failing part fails the whole run.
+    let failed = rows
+        .iter()
+        .any(|row| !matches!(&row.outcome, Ok(run) if run.answer.is_ok()));
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn stats_row(step: &str, stats: &Stats) -> Vec<String> {
+    vec![
+        step.to_string(),
+        report::format_duration(stats.min),
+        report::format_duration(stats.median),
+        report::format_duration(stats.mean),
+        report::format_duration(stats.stddev),
+    ]
+}
+
+fn run_bench(day: u8, part: u8, iterations: u64, warmup: u64, input: Option<String>) -> ExitCode {
+    let part = Part::from_number(part).expect("part is validated by the argument parser");
+    let Some(puzzle) = challenges::find(day) else {
+        eprintln!("{}", Error::NotImplemented { day, part });
+        return ExitCode::FAILURE;
+    };
+    let path = input.unwrap_or_else(|| default_input_path(day));
+    let measurement = input::open(&path)
+        .and_then(input::read)
+        .and_then(|contents| {
+            bench::measure(
+                puzzle.as_ref(),
+                &contents,
+                part,
+                warmup as usize,
+                iterations as usize,
+            )
+        });
+    match measurement {
+        Ok(measurement) => {
+            println!(
+                "day {day} part {}: {} ({iterations} runs after {warmup} warm-up runs)",
+                part.number(),
+                measurement.answer
+            );
+            println!(
+                "{}",
+                report::table(
+                    &["step", "min", "median", "mean", "stddev"],
+                    vec![
+                        stats_row("parse", &measurement.parse),
+                        stats_row("solve", &measurement.solve),
+                    ],
+                )
+            );
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("{}", err.render());
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn verify(answers_path: &str) -> ExitCode {
+    let answers = match Answers::load(answers_path) {
+        Ok(answers) => answers,
+        Err(err) => {
+            eprintln!("{err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut rows: Vec<Vec<String>> = Vec::new();
+    let mut failed = false;
+    for puzzle in challenges::registry() {
+        let day = puzzle.day();
+        let contents = input::open(&default_input_path(day)).and_then(input::read);
+        for part in Part::ALL {
+            let expected = answers.expected(day, part);
+            let (verdict, actual) = match &contents {
+                Ok(contents) => {
+                    let actual = puzzle.solve(contents, part);
+                    let verdict = Verdict::of(expected, actual.as_deref().ok());
+                    (verdict, actual.unwrap_or_else(|err| err.to_string()))
+                }
+                Err(err) => (Verdict::of(expected, None), err.to_string()),
+            };
+            failed |= verdict == Verdict::Fail;
+            rows.push(vec![
+                day.to_string(),
+                part.number().to_string(),
+                verdict.label().to_string(),
+                actual,
+                expected.unwrap_or("-").to_string(),
+            ]);
+        }
+    }
+    println!(
+        "{}",
+        report::table(&["day", "part", "status", "answer", "expected"], rows)
+    );
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn crane(part: u8, final_only: bool, input: Option<String>) -> ExitCode {
+    let path = input.unwrap_or_else(|| default_input_path(5));
+    let traced = input::open(&path)
+        .and_then(input::read)
+        .and_then(|contents| Day05.parse(&contents))
+        .and_then(|(ship, move_instructions)| {
+            let stages = day_05::trace(&ship, &move_instructions, part == 2)?;
+            Ok((ship, stages))
+        });
+    match traced {
+        Ok((ship, stages)) if final_only => {
+            let last = stages.last().map_or(&ship, |stage| &stage.after);
+            println!("{last}");
+            ExitCode::SUCCESS
+        }
+        Ok((_, stages)) => {
+            for (step, stage) in stages.iter().enumerate() {
+                println!("{}. {stage}", step + 1);
+            }
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("{}", err.render());
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn read_transcript(input: Option<String>) -> Result<String, Error> {
+    let path = input.unwrap_or_else(|| default_input_path(7));
+    input::open(&path).and_then(input::read)
+}
+
+fn load_filesystem(input: Option<String>) -> Result<FileSystem, Error> {
+    day_07::parse(&read_transcript(input)?)
+}
+
+fn kind_label(kind: Kind) -> &'static str {
+    match kind {
+        Kind::File => "file",
+        Kind::Directory => "dir",
+    }
+}
+
+fn format_size(size: u64, human_readable: bool) -> String {
+    if human_readable {
+        render::human_size(size)
+    } else {
+        size.to_string()
+    }
+}
+
+fn find(query: Query, human_readable: bool, input: Option<String>) -> Result<(), Error> {
+    let filesystem = load_filesystem(input)?;
+    let rows = query::run(&filesystem, &query)
+        .into_iter()
+        .map(|entry| {
+            vec![
+                kind_label(entry.kind).to_string(),
+                format_size(entry.size, human_readable),
+                entry.path,
+            ]
+        })
+        .collect();
+    println!("{}", report::table(&["type", "size", "path"], rows));
+    Ok(())
+}
+
+fn compare(before: String, after: String, human_readable: bool) -> Result<(), Error> {
+    let before = load_filesystem(Some(before))?;
+    let after = load_filesystem(Some(after))?;
+    let size = |size: Option<u64>| match size {
+        Some(size) => format_size(size, human_readable),
+        None => "-".to_string(),
+    };
+    let rows = diff(&before, &after)
+        .into_iter()
+        .map(|difference| {
+            let change = match difference.change {
+                Change::Added => "+",
+                Change::Removed => "-",
+                Change::Resized => "~",
+            };
+            let delta = difference.delta();
+            let sign = if delta < 0 { "-" } else { "+" };
+            let magnitude = u64::try_from(delta.unsigned_abs())
+                .expect("the difference of two sizes fits in a size");
+            let delta = format!("{sign}{}", format_size(magnitude, human_readable));
+            vec![
+                change.to_string(),
+                kind_label(difference.kind).to_string(),
+                size(difference.before),
+                size(difference.after),
+                delta,
+                difference.path,
+            ]
+        })
+        .collect();
+    println!(
+        "{}",
+        report::table(&["", "type", "before", "after", "delta", "path"], rows)
+    );
+    Ok(())
+}
+
+fn interactive(input: Option<String>) -> Result<(), Error> {
+    if input.as_deref() == Some(input::STDIN) {
+        return Err(Error::Io {
+            message: "the shell reads its commands from standard input, pass a file".to_string(),
+        });
+    }
+    let mut shell = Shell::new(load_filesystem(input)?);
+    println!("type help for the commands");
+    let mut lines = io::stdin().lock().lines();
+    loop {
+        print!("{}$ ", shell.pwd());
+        io::stdout().flush().map_err(|err| Error::Io {
+            message: err.to_string(),
+        })?;
+        let Some(line) = lines.next() else {
+            println!();
+            return Ok(());
+        };
+        let line = line.map_err(|err| Error::Io {
+            message: err.to_string(),
+        })?;
+        match line.trim() {
+            "exit" | "quit" => return Ok(()),
+            command => match shell.execute(command) {
+                Ok(output) if output.is_empty() => {}
+                Ok(output) => println!("{output}"),
+                Err(err) => eprintln!("{err}"),
+            },
+        }
+    }
+}
+
+/// Prints the findings of the consistency checks, fails if one of them is an error.
+fn check(input: Option<String>) -> Result<(), Error> {
+    let contents = read_transcript(input)?;
+    let diagnostics = day_07::validate(contents.lines().collect())?;
+    for diagnostic in diagnostics.iter() {
+        println!("{diagnostic}\n    {}", diagnostic.text);
+    }
+    let errors = diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.severity == Severity::Error)
+        .count();
+    if errors > 0 {
+        let lines = if errors == 1 { "line" } else { "lines" };
+        return Err(Error::invalid(
+            7,
+            &format!("the transcript contradicts itself on {errors} {lines}"),
+        ));
+    }
+    Ok(())
+}
+
+fn fs(command: FsCommand) -> ExitCode {
+    let result = match command {
+        FsCommand::Tree {
+            human_readable,
+            sort_by_size,
+            input,
+        } => load_filesystem(input).map(|filesystem| {
+            let options = RenderOptions {
+                human_readable,
+                sort_by_size,
+            };
+            println!("{}", render::tree(&filesystem, options));
+        }),
+        FsCommand::Materialise { target, input } => {
+            load_filesystem(input).and_then(|filesystem| materialise(&filesystem, &target))
+        }
+        FsCommand::Find {
+            filter,
+            largest,
+            human_readable,
+            input,
+        } => find(Query { filter, largest }, human_readable, input),
+        FsCommand::Diff {
+            before,
+            after,
+            human_readable,
+        } => compare(before, after, human_readable),
+        FsCommand::Check { input } => check(input),
+        FsCommand::Shell { input } => interactive(input),
+        FsCommand::Export { input } => {
+            load_filesystem(input).map(|filesystem| println!("{}", json::export(&filesystem)))
+        }
+        FsCommand::Transcript { directory } => {
+            scan(&directory).map(|filesystem| println!("{}", transcript(&filesystem)))
+        }
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{}", err.render());
+            ExitCode::FAILURE
+        }
+    }
+}
 
-    let output = challenges::day_07::assembly(contents.lines().collect());
+fn main() -> ExitCode {
+    let cli = Cli::parse();
 
-    println!("sum {}", output)
+    match cli.command {
+        Command::Run {
+            all: true, format, ..
+        } => run_all(format),
+        Command::Run {
+            day: Some(day),
+            part,
+            input,
+            input_path,
+            format,
+            capacity,
+            required_free,
+            ..
+        } => run_single(
+            day,
+            part,
+            input.or(input_path),
+            (capacity, required_free),
+            format,
+        ),
+        Command::Run { day: None, .. } => unreachable!("day is required unless --all is given"),
+        Command::Bench {
+            day,
+            part,
+            iterations,
+            warmup,
+            input,
+        } => run_bench(day, part, iterations, warmup, input),
+        Command::Verify { answers } => verify(&answers),
+        Command::Crane {
+            part,
+            final_only,
+            input,
+        } => crane(part, final_only, input),
+        Command::Fs { command } => fs(command),
+    }
 }
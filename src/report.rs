@@ -0,0 +1,195 @@
+use aoc_2022::challenges::{Part, Run};
+use aoc_2022::error::Error;
+use serde::Serialize;
+use std::time::Duration;
+
+/// Outcome of running a single part, either on its own or as a line of the run-all summary.
+pub struct Row {
+    pub day: u8,
+    pub part: Part,
+    pub outcome: Result<Run, Error>,
+}
+
+#[derive(Serialize)]
+struct JsonError<'a> {
+    description: String,
+    #[serde(flatten)]
+    details: &'a Error,
+}
+
+#[derive(Serialize)]
+struct JsonRow<'a> {
+    day: u8,
+    part: Part,
+    answer: Option<&'a str>,
+    parse_ns: Option<u64>,
+    solve_ns: Option<u64>,
+    error: Option<JsonError<'a>>,
+}
+
+impl<'a> JsonError<'a> {
+    fn new(error: &'a Error) -> Self {
+        JsonError {
+            description: error.to_string(),
+            details: error,
+        }
+    }
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    format!("{duration:.2?}")
+}
+
+fn cells(row: &Row) -> Vec<String> {
+    let (answer, parse_time, solve_time) = match &row.outcome {
+        Ok(run) => (
+            match &run.answer {
+                Ok(answer) => answer.clone(),
+                Err(err) => err.to_string(),
+            },
+            format_duration(run.parse_time),
+            format_duration(run.solve_time),
+        ),
+        Err(err) => (err.to_string(), String::new(), String::new()),
+    };
+    vec![
+        row.day.to_string(),
+        row.part.number().to_string(),
+        answer,
+        parse_time,
+        solve_time,
+    ]
+}
+
+/// Renders the summary of a run over all days.
+pub fn run_table(rows: &[Row]) -> String {
+    table(
+        &["day", "part", "answer", "parse", "solve"],
+        rows.iter().map(cells).collect(),
+    )
+}
+
+/// Renders a row as a single line JSON object, timings are given in nanoseconds.
+pub fn json_line(row: &Row) -> String {
+    let (answer, parse_ns, solve_ns, error) = match &row.outcome {
+        Ok(run) => (
+            run.answer.as_deref().ok(),
+            Some(run.parse_time.as_nanos() as u64),
+            Some(run.solve_time.as_nanos() as u64),
+            run.answer.as_ref().err().map(JsonError::new),
+        ),
+        Err(err) => (None, None, None, Some(JsonError::new(err))),
+    };
+    serde_json::to_string(&JsonRow {
+        day: row.day,
+        part: row.part,
+        answer,
+        parse_ns,
+        solve_ns,
+        error,
+    })
+    .expect("rows only contain plain strings and numbers")
+}
+
+/// Renders the rows as an aligned table with a header line.
+pub fn table(header: &[&str], rows: Vec<Vec<String>>) -> String {
+    let header: Vec<String> = header.iter().map(|cell| cell.to_string()).collect();
+    let lines: Vec<Vec<String>> = std::iter::once(header).chain(rows).collect();
+
+    let mut widths: Vec<usize> = vec![0; lines[0].len()];
+    for line in lines.iter() {
+        for (width, cell) in widths.iter_mut().zip(line.iter()) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    lines
+        .iter()
+        .map(|line| {
+            line.iter()
+                .zip(widths.iter())
+                .map(|(cell, width)| format!("{cell:<width$}"))
+                .collect::<Vec<String>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{json_line, run_table, Row};
+    use aoc_2022::challenges::{Part, Run};
+    use aoc_2022::error::Error;
+    use std::time::Duration;
+
+    #[test]
+    fn test_table() {
+        let rows = vec![
+            Row {
+                day: 5,
+                part: Part::Two,
+                outcome: Ok(Run {
+                    answer: Ok("MCD".to_string()),
+                    parse_time: Duration::from_millis(2),
+                    solve_time: Duration::from_micros(15),
+                }),
+            },
+            Row {
+                day: 6,
+                part: Part::One,
+                outcome: Err(Error::Io {
+                    message: "missing data/day_06.txt".to_string(),
+                }),
+            },
+        ];
+
+        let expected = "day  part  answer                   parse   solve
+5    2     MCD                      2.00ms  15.00µs
+6    1     missing data/day_06.txt";
+
+        assert_eq!(run_table(&rows), expected);
+    }
+
+    #[test]
+    fn test_json_line() {
+        let row = Row {
+            day: 5,
+            part: Part::Two,
+            outcome: Ok(Run {
+                answer: Ok("MCD".to_string()),
+                parse_time: Duration::from_micros(2),
+                solve_time: Duration::from_nanos(150),
+            }),
+        };
+        assert_eq!(
+            json_line(&row),
+            r#"{"day":5,"part":2,"answer":"MCD","parse_ns":2000,"solve_ns":150,"error":null}"#
+        );
+    }
+
+    #[test]
+    fn test_json_line_with_error() {
+        let row = Row {
+            day: 4,
+            part: Part::One,
+            outcome: Ok(Run {
+                answer: Err(Error::parse(
+                    4,
+                    2,
+                    3,
+                    "2-x,6-8",
+                    "expected a section number",
+                )),
+                parse_time: Duration::from_nanos(10),
+                solve_time: Duration::from_nanos(20),
+            }),
+        };
+        assert_eq!(
+            json_line(&row),
+            r#"{"day":4,"part":1,"answer":null,"parse_ns":10,"solve_ns":20,"error":{"description":"day 4, line 2, column 3: expected a section number","kind":"parse","day":4,"line":2,"column":3,"text":"2-x,6-8","message":"expected a section number"}}"#
+        );
+    }
+}
@@ -0,0 +1,91 @@
+//! Runs every puzzle example under `examples/`.
+//!
+//! An example is a file `examples/day_NN_<name>.txt` holding the puzzle input, paired with
+//! `examples/day_NN_<name>.toml` holding the expected answers as `part_one` and `part_two`.
+
+use aoc_2022::answers;
+use aoc_2022::challenges::{self, Part};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+struct Example {
+    day: u8,
+    input: PathBuf,
+    answers: PathBuf,
+}
+
+fn day_of(file_name: &str) -> Option<u8> {
+    let rest = file_name.strip_prefix("day_")?;
+    rest.get(0..2)?.parse().ok()
+}
+
+fn discover(directory: &Path) -> Vec<Example> {
+    let mut examples: Vec<Example> = fs::read_dir(directory)
+        .expect("examples directory should be readable")
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "txt"))
+        .filter_map(|input| {
+            let day = day_of(input.file_name()?.to_str()?)?;
+            let answers = input.with_extension("toml");
+            Some(Example {
+                day,
+                input,
+                answers,
+            })
+        })
+        .collect();
+    examples.sort_by(|left, right| left.input.cmp(&right.input));
+    examples
+}
+
+fn check(example: &Example) -> Vec<String> {
+    let name = example.input.display();
+    let Some(puzzle) = challenges::find(example.day) else {
+        return vec![format!("{name}: day {} is not implemented", example.day)];
+    };
+    let expected = match fs::read_to_string(&example.answers)
+        .map_err(|err| err.to_string())
+        .and_then(|contents| answers::parse_parts(&contents))
+    {
+        Ok(expected) => expected,
+        Err(err) => return vec![format!("{}: {err}", example.answers.display())],
+    };
+    let input = fs::read_to_string(&example.input).unwrap();
+
+    Part::ALL
+        .into_iter()
+        .filter_map(|part| {
+            let expected = expected.get(&part)?;
+            match puzzle.solve(&input, part) {
+                Ok(actual) if &actual == expected => None,
+                Ok(actual) => Some(format!(
+                    "{name} part {}: expected {expected}, got {actual}",
+                    part.number()
+                )),
+                Err(err) => Some(format!("{name} part {}: {err}", part.number())),
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn examples_match_their_answers() {
+    let examples = discover(&Path::new(env!("CARGO_MANIFEST_DIR")).join("examples"));
+
+    let failures: Vec<String> = examples.iter().flat_map(check).collect();
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
+
+#[test]
+fn every_day_has_an_example() {
+    let examples = discover(&Path::new(env!("CARGO_MANIFEST_DIR")).join("examples"));
+    let covered: BTreeSet<u8> = examples.iter().map(|example| example.day).collect();
+
+    let missing: Vec<u8> = challenges::registry()
+        .iter()
+        .map(|puzzle| puzzle.day())
+        .filter(|day| !covered.contains(day))
+        .collect();
+    assert!(missing.is_empty(), "days without an example: {missing:?}");
+}